@@ -0,0 +1,155 @@
+//! Build-time generator for cluster scaffolding.
+//!
+//! When the `gen_clusters` feature is enabled this reads the declarative
+//! `cluster_defs.in` table and emits `clusters_gen.rs` into `OUT_DIR`, one
+//! module per cluster containing the id constants, per-attribute factory
+//! functions and a `command_name` lookup from command id to command name. No
+//! dispatch code is generated: the cluster's hand-written `handle_command`
+//! still matches on the generated `CMD_*_ID` constants and runs its handlers.
+//! Hand-written clusters keep working when the feature is off, so the
+//! generator is purely additive.
+
+use std::env;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+fn main() {
+    println!("cargo:rerun-if-changed=src/data_model/cluster_defs.in");
+    if env::var_os("CARGO_FEATURE_GEN_CLUSTERS").is_none() {
+        return;
+    }
+
+    let defs = fs::read_to_string("src/data_model/cluster_defs.in")
+        .expect("cluster_defs.in must be present when gen_clusters is enabled");
+    let generated = generate(&defs);
+
+    let out = Path::new(&env::var("OUT_DIR").unwrap()).join("clusters_gen.rs");
+    fs::write(out, generated).unwrap();
+}
+
+struct Attr {
+    name: String,
+    id: String,
+    value: String,
+}
+
+struct Cmd {
+    name: String,
+    id: String,
+}
+
+#[derive(Default)]
+struct ClusterDef {
+    name: String,
+    id: String,
+    attrs: Vec<Attr>,
+    cmds: Vec<Cmd>,
+}
+
+// Map a declarative (type, default) pair onto the matching `AttrValue` variant.
+fn attr_value(ty: &str, default: &str) -> String {
+    match ty {
+        "bool" => format!("AttrValue::Bool({})", default),
+        "u8" => format!("AttrValue::Uint8({})", default),
+        "u16" => format!("AttrValue::Uint16({})", default),
+        other => panic!("unsupported attribute type: {}", other),
+    }
+}
+
+fn generate(defs: &str) -> String {
+    let mut clusters: Vec<ClusterDef> = Vec::new();
+    for line in defs.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let f: Vec<&str> = line.split_whitespace().collect();
+        match f[0] {
+            "cluster" => clusters.push(ClusterDef {
+                name: f[1].to_string(),
+                id: f[2].to_string(),
+                ..Default::default()
+            }),
+            "attr" => clusters.last_mut().unwrap().attrs.push(Attr {
+                name: f[1].to_string(),
+                id: f[2].to_string(),
+                value: attr_value(f[3], f[4]),
+            }),
+            "cmd" => clusters.last_mut().unwrap().cmds.push(Cmd {
+                name: f[1].to_string(),
+                id: f[2].to_string(),
+            }),
+            other => panic!("unknown directive: {}", other),
+        }
+    }
+
+    let mut s = String::new();
+    s.push_str("// @generated by build.rs from cluster_defs.in - do not edit.\n");
+    for c in &clusters {
+        let modname = c.name.to_lowercase();
+        writeln!(s, "pub mod {} {{", modname).unwrap();
+        s.push_str("    use super::super::objects::*;\n");
+        s.push_str("    use crate::error::Error;\n\n");
+        writeln!(
+            s,
+            "    pub const CLUSTER_{}_ID: u32 = {};",
+            c.name.to_uppercase(),
+            c.id
+        )
+        .unwrap();
+        for a in &c.attrs {
+            writeln!(
+                s,
+                "    pub const ATTR_{}_ID: u16 = {};",
+                a.name.to_uppercase(),
+                a.id
+            )
+            .unwrap();
+        }
+        for cmd in &c.cmds {
+            writeln!(
+                s,
+                "    pub const CMD_{}_ID: u16 = {};",
+                cmd.name.to_uppercase(),
+                cmd.id
+            )
+            .unwrap();
+        }
+        s.push('\n');
+        for a in &c.attrs {
+            writeln!(
+                s,
+                "    pub fn attr_{}_new() -> Result<Box<Attribute>, Error> {{",
+                a.name.to_lowercase()
+            )
+            .unwrap();
+            writeln!(
+                s,
+                "        Attribute::new(ATTR_{}_ID, {})",
+                a.name.to_uppercase(),
+                a.value
+            )
+            .unwrap();
+            s.push_str("    }\n");
+        }
+        s.push('\n');
+        // A dispatch skeleton mapping command ids to their names; the cluster's
+        // own `handle_command` matches on these and runs the user handler.
+        s.push_str("    pub fn command_name(id: u16) -> Option<&'static str> {\n");
+        s.push_str("        match id {\n");
+        for cmd in &c.cmds {
+            writeln!(
+                s,
+                "            CMD_{}_ID => Some(\"{}\"),",
+                cmd.name.to_uppercase(),
+                cmd.name
+            )
+            .unwrap();
+        }
+        s.push_str("            _ => None,\n");
+        s.push_str("        }\n    }\n");
+        s.push_str("}\n\n");
+    }
+    s
+}
@@ -0,0 +1,278 @@
+use super::{validate_canonical_der, CertConsumer, MAX_DEPTH};
+use crate::error::Error;
+use chrono::{TimeZone, Utc};
+
+/// A DER reader that walks a buffer and drives a [`CertConsumer`] with the same
+/// vocabulary [`ASN1Writer`](super::asn1_writer::ASN1Writer) emits, so an
+/// incoming X.509/Matter certificate can be parsed back out symmetrically to
+/// the way one is serialized. This is what lets the crate validate Device
+/// Attestation Certificates and PAA roots rather than only emit them.
+pub struct ASN1Reader<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> ASN1Reader<'a> {
+    pub fn new(buf: &'a [u8]) -> Self {
+        Self { buf }
+    }
+
+    /// Walk the buffer, invoking `consumer` for every element encountered.
+    pub fn parse(&self, consumer: &mut dyn CertConsumer) -> Result<(), Error> {
+        // Reject structurally malformed / non-canonical external DER up-front,
+        // so a corrupt input fails with a distinct `Error::InvalidDer` rather
+        // than producing a half-populated consumer.
+        validate_canonical_der(self.buf)?;
+        let consumed = self.parse_element(self.buf, consumer, 0)?;
+        if consumed != self.buf.len() {
+            return Err(Error::InvalidData);
+        }
+        Ok(())
+    }
+
+    // Parse one element off the front of `buf`, recursing into constructed
+    // values, and return how many bytes it occupied.
+    fn parse_element(
+        &self,
+        buf: &[u8],
+        consumer: &mut dyn CertConsumer,
+        depth: usize,
+    ) -> Result<usize, Error> {
+        if depth >= MAX_DEPTH {
+            return Err(Error::InvalidData);
+        }
+        if buf.len() < 2 {
+            return Err(Error::InvalidData);
+        }
+        let tag = buf[0];
+        let (len, len_octets) = Self::decode_len(&buf[1..])?;
+        let header = 1 + len_octets;
+        let end = header.checked_add(len).ok_or(Error::InvalidData)?;
+        if end > buf.len() {
+            return Err(Error::InvalidData);
+        }
+        let content = &buf[header..end];
+
+        match tag {
+            // SEQUENCE
+            0x30 => {
+                consumer.start_seq("")?;
+                self.parse_children(content, consumer, depth)?;
+                consumer.end_seq()?;
+            }
+            // SET
+            0x31 => {
+                consumer.start_set("")?;
+                self.parse_children(content, consumer, depth)?;
+                consumer.end_set()?;
+            }
+            0x02 => consumer.integer("", content)?,
+            0x0c => {
+                let s = core::str::from_utf8(content).map_err(|_| Error::InvalidData)?;
+                consumer.utf8str("", s)?;
+            }
+            // BIT STRING: the first octet is the number of unused trailing
+            // bits. The remaining octets are handed to the consumer unchanged,
+            // with `truncate` set when that count is non-zero; whole zero
+            // octets stripped by the writer are not restored.
+            0x03 => {
+                if content.is_empty() {
+                    return Err(Error::InvalidData);
+                }
+                consumer.bitstr("", content[0] != 0, &content[1..])?;
+            }
+            0x04 => consumer.ostr("", content)?,
+            0x01 => consumer.bool("", content.first().map(|b| *b != 0).unwrap_or(false))?,
+            0x06 => consumer.oid("", content)?,
+            0x17 => consumer.utctime("", Self::decode_utctime(content)?)?,
+            // Constructed context-specific [n]: 0xA0 | n.
+            t if t & 0xe0 == 0xa0 => {
+                consumer.start_ctx("", t & 0x1f)?;
+                self.parse_children(content, consumer, depth)?;
+                consumer.end_ctx()?;
+            }
+            // Primitive context-specific [n]: 0x80 | n.
+            t if t & 0xe0 == 0x80 => consumer.ctx("", t & 0x1f, content)?,
+            _ => return Err(Error::InvalidData),
+        }
+        Ok(end)
+    }
+
+    fn parse_children(
+        &self,
+        content: &[u8],
+        consumer: &mut dyn CertConsumer,
+        depth: usize,
+    ) -> Result<(), Error> {
+        let mut off = 0;
+        while off < content.len() {
+            off += self.parse_element(&content[off..], consumer, depth + 1)?;
+        }
+        Ok(())
+    }
+
+    // Decode a definite-length, mirroring `ASN1Writer::encode_len`: the short
+    // form for 0..=127, else `0x80 | n` followed by `n` big-endian octets.
+    fn decode_len(buf: &[u8]) -> Result<(usize, usize), Error> {
+        let first = *buf.first().ok_or(Error::InvalidData)?;
+        if first & 0x80 == 0 {
+            return Ok((first as usize, 1));
+        }
+        let num = (first & 0x7f) as usize;
+        if num == 0 || num > 4 || buf.len() < 1 + num {
+            return Err(Error::InvalidData);
+        }
+        let mut len = 0usize;
+        for &b in &buf[1..1 + num] {
+            len = (len << 8) | b as usize;
+        }
+        Ok((len, 1 + num))
+    }
+
+    // Reconstruct the Matter 2000-based epoch offset from a `YYMMDDHHMMSSZ`
+    // UTCTime string, the inverse of `ASN1Writer::utctime`.
+    fn decode_utctime(content: &[u8]) -> Result<u32, Error> {
+        let s = core::str::from_utf8(content).map_err(|_| Error::InvalidData)?;
+        if s.len() != 13 || !s.ends_with('Z') {
+            return Err(Error::InvalidData);
+        }
+        let num = |a: usize, b: usize| s[a..b].parse::<u32>().map_err(|_| Error::InvalidData);
+        let yy = num(0, 2)?;
+        let (mon, day, hour, min, sec) = (num(2, 4)?, num(4, 6)?, num(6, 8)?, num(8, 10)?, num(10, 12)?);
+        let dt = Utc
+            .ymd_opt(2000 + yy as i32, mon, day)
+            .single()
+            .ok_or(Error::InvalidData)?
+            .and_hms_opt(hour, min, sec)
+            .ok_or(Error::InvalidData)?;
+        let matter_epoch = Utc.ymd(2000, 1, 1).and_hms(0, 0, 0).timestamp();
+        Ok((dt.timestamp() - matter_epoch) as u32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ASN1Reader;
+    use crate::cert::CertConsumer;
+    use crate::error::Error;
+
+    // A consumer that records the shape of what the reader visits.
+    #[derive(Default)]
+    struct Recorder {
+        log: Vec<String>,
+    }
+
+    impl CertConsumer for Recorder {
+        fn start_seq(&mut self, _tag: &str) -> Result<(), Error> {
+            self.log.push("seq{".into());
+            Ok(())
+        }
+        fn end_seq(&mut self) -> Result<(), Error> {
+            self.log.push("}".into());
+            Ok(())
+        }
+        fn integer(&mut self, _tag: &str, i: &[u8]) -> Result<(), Error> {
+            self.log.push(format!("int({})", i.len()));
+            Ok(())
+        }
+        fn utf8str(&mut self, _tag: &str, s: &str) -> Result<(), Error> {
+            self.log.push(format!("utf8({})", s));
+            Ok(())
+        }
+        fn bitstr(&mut self, _tag: &str, _truncate: bool, s: &[u8]) -> Result<(), Error> {
+            self.log.push(format!("bits({})", s.len()));
+            Ok(())
+        }
+        fn ostr(&mut self, _tag: &str, s: &[u8]) -> Result<(), Error> {
+            self.log.push(format!("ostr({})", s.len()));
+            Ok(())
+        }
+        fn start_compound_ostr(&mut self, _tag: &str) -> Result<(), Error> {
+            Ok(())
+        }
+        fn end_compound_ostr(&mut self) -> Result<(), Error> {
+            Ok(())
+        }
+        fn bool(&mut self, _tag: &str, b: bool) -> Result<(), Error> {
+            self.log.push(format!("bool({})", b));
+            Ok(())
+        }
+        fn start_set(&mut self, _tag: &str) -> Result<(), Error> {
+            self.log.push("set{".into());
+            Ok(())
+        }
+        fn end_set(&mut self) -> Result<(), Error> {
+            self.log.push("}".into());
+            Ok(())
+        }
+        fn ctx(&mut self, _tag: &str, id: u8, _val: &[u8]) -> Result<(), Error> {
+            self.log.push(format!("ctx[{}]", id));
+            Ok(())
+        }
+        fn start_ctx(&mut self, _tag: &str, id: u8) -> Result<(), Error> {
+            self.log.push(format!("ctx[{}]{{", id));
+            Ok(())
+        }
+        fn end_ctx(&mut self) -> Result<(), Error> {
+            self.log.push("}".into());
+            Ok(())
+        }
+        fn oid(&mut self, _tag: &str, oid: &[u8]) -> Result<(), Error> {
+            self.log.push(format!("oid({})", oid.len()));
+            Ok(())
+        }
+        fn utctime(&mut self, _tag: &str, epoch: u32) -> Result<(), Error> {
+            self.log.push(format!("utctime({})", epoch));
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_parse_sequence() {
+        // SEQUENCE { INTEGER 1, BOOLEAN true, UTF8String "AB" }
+        let der = [
+            0x30, 0x0a, 0x02, 0x01, 0x01, 0x01, 0x01, 0xff, 0x0c, 0x02, b'A', b'B',
+        ];
+        let mut rec = Recorder::default();
+        ASN1Reader::new(&der).parse(&mut rec).unwrap();
+        assert_eq!(
+            rec.log,
+            vec!["seq{", "int(1)", "bool(true)", "utf8(AB)", "}"]
+        );
+    }
+
+    #[test]
+    fn test_parse_utctime_roundtrip() {
+        use crate::cert::asn1_writer::ASN1Writer;
+        // Emit a UTCTime for a known epoch, then read it back symmetrically.
+        let mut buf = [0u8; 32];
+        let len = {
+            let mut w = ASN1Writer::new(&mut buf);
+            w.utctime("", 12_345_678).unwrap();
+            w.as_slice().len()
+        };
+        let mut rec = Recorder::default();
+        ASN1Reader::new(&buf[..len]).parse(&mut rec).unwrap();
+        assert_eq!(rec.log, vec!["utctime(12345678)"]);
+    }
+
+    #[test]
+    fn test_accept_keyusage_bitstring() {
+        // A KeyUsage BIT STRING legitimately carries non-zero unused bits
+        // (`03 02 07 80` = digitalSignature). The canonical-DER gate must let
+        // it through so real DAC/PAI/PAA/NOC certificates can be parsed.
+        let der = [0x03, 0x02, 0x07, 0x80];
+        let mut rec = Recorder::default();
+        ASN1Reader::new(&der).parse(&mut rec).unwrap();
+        assert_eq!(rec.log, vec!["bits(1)"]);
+    }
+
+    #[test]
+    fn test_reject_trailing_bytes() {
+        // Trailing bytes after the top-level element are now caught by the
+        // canonical-DER validator that runs before parsing, with the distinct
+        // `InvalidDer` error.
+        let der = [0x02, 0x01, 0x01, 0x00];
+        let mut rec = Recorder::default();
+        assert_eq!(Err(Error::InvalidDer), ASN1Reader::new(&der).parse(&mut rec));
+    }
+}
@@ -14,7 +14,10 @@ pub struct ASN1Writer<'a> {
     current_depth: usize,
 }
 
-const RESERVE_LEN_BYTES: usize = 3;
+// The largest length encoding is the four-octet long form (0x84 + 4 bytes),
+// so a compound reserves five bytes up-front and collapses the unused span on
+// `end_compound`.
+const RESERVE_LEN_BYTES: usize = 5;
 impl<'a> ASN1Writer<'a> {
     pub fn new(buf: &'a mut [u8]) -> Self {
         Self {
@@ -122,11 +125,17 @@ impl<'a> ASN1Writer<'a> {
             // This is directly encoded
             1
         } else if len < 256 {
-            // This is done with an 0xA1 followed by actual len
+            // This is done with an 0x81 followed by actual len
             2
         } else if len < 65536 {
-            // This is done with an 0xA2 followed by 2 bytes of actual len
+            // This is done with an 0x82 followed by 2 bytes of actual len
             3
+        } else if len < 0x0100_0000 {
+            // 0x83 followed by 3 bytes of actual len
+            4
+        } else if len <= 0xffff_ffff {
+            // 0x84 followed by 4 bytes of actual len
+            5
         } else {
             return Err(Error::NoSpace);
         };
@@ -149,6 +158,105 @@ impl<'a> ASN1Writer<'a> {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::ASN1Writer;
+    use crate::cert::asn1_reader::ASN1Reader;
+    use crate::cert::CertConsumer;
+    use crate::error::Error;
+
+    // Captures the single octet-string length the reader reports back.
+    #[derive(Default)]
+    struct OstrLen(Option<usize>);
+    impl CertConsumer for OstrLen {
+        fn start_seq(&mut self, _: &str) -> Result<(), Error> {
+            Ok(())
+        }
+        fn end_seq(&mut self) -> Result<(), Error> {
+            Ok(())
+        }
+        fn integer(&mut self, _: &str, _: &[u8]) -> Result<(), Error> {
+            Ok(())
+        }
+        fn utf8str(&mut self, _: &str, _: &str) -> Result<(), Error> {
+            Ok(())
+        }
+        fn bitstr(&mut self, _: &str, _: bool, _: &[u8]) -> Result<(), Error> {
+            Ok(())
+        }
+        fn ostr(&mut self, _: &str, s: &[u8]) -> Result<(), Error> {
+            self.0 = Some(s.len());
+            Ok(())
+        }
+        fn start_compound_ostr(&mut self, _: &str) -> Result<(), Error> {
+            Ok(())
+        }
+        fn end_compound_ostr(&mut self) -> Result<(), Error> {
+            Ok(())
+        }
+        fn bool(&mut self, _: &str, _: bool) -> Result<(), Error> {
+            Ok(())
+        }
+        fn start_set(&mut self, _: &str) -> Result<(), Error> {
+            Ok(())
+        }
+        fn end_set(&mut self) -> Result<(), Error> {
+            Ok(())
+        }
+        fn ctx(&mut self, _: &str, _: u8, _: &[u8]) -> Result<(), Error> {
+            Ok(())
+        }
+        fn start_ctx(&mut self, _: &str, _: u8) -> Result<(), Error> {
+            Ok(())
+        }
+        fn end_ctx(&mut self) -> Result<(), Error> {
+            Ok(())
+        }
+        fn oid(&mut self, _: &str, _: &[u8]) -> Result<(), Error> {
+            Ok(())
+        }
+        fn utctime(&mut self, _: &str, _: u32) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_bytes_to_encode_len_boundaries() {
+        assert_eq!(1, ASN1Writer::bytes_to_encode_len(127).unwrap());
+        assert_eq!(2, ASN1Writer::bytes_to_encode_len(128).unwrap());
+        assert_eq!(2, ASN1Writer::bytes_to_encode_len(255).unwrap());
+        assert_eq!(3, ASN1Writer::bytes_to_encode_len(256).unwrap());
+        assert_eq!(3, ASN1Writer::bytes_to_encode_len(65535).unwrap());
+        assert_eq!(4, ASN1Writer::bytes_to_encode_len(65536).unwrap());
+        assert_eq!(5, ASN1Writer::bytes_to_encode_len(0x0100_0000).unwrap());
+        assert!(ASN1Writer::bytes_to_encode_len(0x1_0000_0000).is_err());
+    }
+
+    // Encode an octet string of `n` bytes inside a SEQUENCE and confirm it
+    // round-trips through the reader, exercising the short form and the one-,
+    // two- and three-octet long forms (the four-octet form is not reached).
+    fn roundtrip(n: usize) {
+        let mut buf = vec![0u8; n + 16];
+        let len = {
+            let mut w = ASN1Writer::new(&mut buf);
+            w.start_seq("").unwrap();
+            w.ostr("", &vec![0u8; n]).unwrap();
+            w.end_seq().unwrap();
+            w.as_slice().len()
+        };
+        let mut seen = OstrLen::default();
+        ASN1Reader::new(&buf[..len]).parse(&mut seen).unwrap();
+        assert_eq!(seen.0, Some(n));
+    }
+
+    #[test]
+    fn test_roundtrip_length_boundaries() {
+        for n in [0, 127, 128, 255, 256, 65535, 65536, 70000] {
+            roundtrip(n);
+        }
+    }
+}
+
 impl<'a> CertConsumer for ASN1Writer<'a> {
     fn start_seq(&mut self, _tag: &str) -> Result<(), Error> {
         self.add_compound(0x30)
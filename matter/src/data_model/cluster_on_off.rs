@@ -8,14 +8,31 @@ use crate::{
 };
 use log::info;
 
+// When the generator is enabled the id constants and the attribute factory
+// come from `cluster_defs.in` via build.rs; otherwise they are hand-written
+// below. Both paths produce byte-identical behaviour.
+#[cfg(feature = "gen_clusters")]
+include!(concat!(env!("OUT_DIR"), "/clusters_gen.rs"));
+
+#[cfg(feature = "gen_clusters")]
+use onoff::{
+    attr_onoff_new as attr_on_off_new, CLUSTER_ONOFF_ID, CMD_OFF_ID, CMD_ON_ID, CMD_TOGGLE_ID,
+};
+
+#[cfg(not(feature = "gen_clusters"))]
 const CLUSTER_ONOFF_ID: u32 = 0x0006;
 
+#[cfg(not(feature = "gen_clusters"))]
 const ATTR_ON_OFF_ID: u16 = 0x0;
 
+#[cfg(not(feature = "gen_clusters"))]
 const CMD_OFF_ID: u16 = 0x00;
+#[cfg(not(feature = "gen_clusters"))]
 const CMD_ON_ID: u16 = 0x01;
+#[cfg(not(feature = "gen_clusters"))]
 const CMD_TOGGLE_ID: u16 = 0x02;
 
+#[cfg(not(feature = "gen_clusters"))]
 fn attr_on_off_new() -> Result<Box<Attribute>, Error> {
     // Id: 0, Value: false
     Attribute::new(ATTR_ON_OFF_ID, AttrValue::Bool(false))
@@ -142,13 +142,14 @@ pub mod ib {
 
     impl<'a> AttrDataIn<'a> {
         pub fn from_tlv(attr_data: &TLVElement<'a>) -> Result<Self, Error> {
-            let data_version = attr_data.find_tag(Tag::DataVersion as u32);
-            if data_version.is_ok() {
-                let _data_version = data_version?.get_u8()?;
-                error!("Data Version handling not yet supported");
-            }
             let path = attr_data.find_tag(Tag::Path as u32)?;
-            let path = AttrPath::from_tlv(&path)?;
+            let mut path = AttrPath::from_tlv(&path)?;
+            // A write may carry the DataVersion it last saw as a precondition;
+            // fold it into the path so the handler can compare against the
+            // cluster's live version before applying the mutation.
+            if let Ok(data_version) = attr_data.find_tag(Tag::DataVersion as u32) {
+                path.data_version = data_version.get_u32().ok();
+            }
             let data = attr_data.find_tag(Tag::Data as u32)?;
             Ok(Self { path, data })
         }
@@ -167,11 +168,20 @@ pub mod ib {
         pub fn new(path: AttrPath, data: F) -> Self {
             Self { path, data }
         }
+
+        // The read handler fills in the cluster's current DataVersion so a
+        // controller can cache it and short-circuit future reports.
+        pub fn with_data_version(mut self, data_version: u32) -> Self {
+            self.path.data_version = Some(data_version);
+            self
+        }
     }
 
     impl<F: Fn(TagType, &mut TLVWriter) -> Result<(), Error>> ToTLV for AttrDataOut<F> {
         fn to_tlv(&self, tw: &mut TLVWriter, tag_type: TagType) -> Result<(), Error> {
             tw.put_start_struct(tag_type)?;
+            // The DataVersion rides along inside the AttrPath list (see
+            // `AttrPathTag::DataVersion`), so emitting the path carries it too.
             tw.put_object(TagType::Context(Tag::Path as u8), &self.path)?;
             (self.data)(TagType::Context(Tag::Data as u8), tw)?;
             tw.put_end_container()
@@ -192,6 +202,12 @@ pub mod ib {
                 status: super::ib::Status::new(status, cluster_status),
             }
         }
+
+        // Emitted when a write's DataVersion precondition does not match the
+        // cluster's current version; the mutation is rejected and left untouched.
+        pub fn data_version_mismatch(path: &GenericPath) -> Self {
+            AttrStatus::new(path, IMStatusCode::DataVersionMismatch, 0)
+        }
     }
 
     impl ToTLV for AttrStatus {
@@ -212,6 +228,7 @@ pub mod ib {
         pub node: Option<u64>,
         pub path: GenericPath,
         pub list_index: Option<u16>,
+        pub data_version: Option<u32>,
     }
 
     #[derive(FromPrimitive)]
@@ -222,6 +239,7 @@ pub mod ib {
         Cluster = 3,
         Attribute = 4,
         ListIndex = 5,
+        DataVersion = 6,
     }
 
     impl AttrPath {
@@ -252,6 +270,7 @@ pub mod ib {
                         AttrPathTag::Cluster => ib.path.cluster = i.get_u8().map(|a| a as u32).ok(),
                         AttrPathTag::Attribute => ib.path.leaf = i.get_u16().map(|a| a as u32).ok(),
                         AttrPathTag::ListIndex => ib.list_index = i.get_u16().ok(),
+                        AttrPathTag::DataVersion => ib.data_version = i.get_u32().ok(),
                     },
                     _ => error!("Unsupported tag"),
                 }
@@ -272,10 +291,171 @@ pub mod ib {
             if let Some(v) = self.path.leaf {
                 tw.put_u16(TagType::Context(AttrPathTag::Attribute as u8), v as u16)?;
             }
+            if let Some(v) = self.data_version {
+                tw.put_u32(TagType::Context(AttrPathTag::DataVersion as u8), v)?;
+            }
             tw.put_end_container()
         }
     }
 
+    // Data Version Filter
+    // A controller advertises the DataVersion it already holds for a cluster so
+    // the server can skip emitting AttributeReportIb entries that would not have
+    // changed. Mismatch (or absence) means the controller gets a fresh report.
+    #[derive(Default, Clone, Copy, Debug)]
+    pub struct DataVersionFilter {
+        pub path: GenericPath,
+        pub data_version: u32,
+    }
+
+    #[derive(FromPrimitive)]
+    pub enum DataVersionFilterTag {
+        Path = 0,
+        DataVersion = 1,
+    }
+
+    #[derive(FromPrimitive)]
+    pub enum ClusterPathTag {
+        Node = 0,
+        Endpoint = 1,
+        Cluster = 2,
+    }
+
+    impl DataVersionFilter {
+        pub fn from_tlv(filter: &TLVElement) -> Result<Self, Error> {
+            let mut ib = DataVersionFilter::default();
+            let path = filter.find_tag(DataVersionFilterTag::Path as u32)?;
+            let iter = path.confirm_list()?.iter().ok_or(Error::Invalid)?;
+            for i in iter {
+                match i.get_tag() {
+                    TagType::Context(t) => {
+                        match num::FromPrimitive::from_u8(t).ok_or(Error::Invalid)? {
+                            ClusterPathTag::Node => {}
+                            ClusterPathTag::Endpoint => {
+                                ib.path.endpoint = i.get_u8().map(|a| a as u16).ok()
+                            }
+                            ClusterPathTag::Cluster => {
+                                ib.path.cluster = i.get_u8().map(|a| a as u32).ok()
+                            }
+                        }
+                    }
+                    _ => error!("Unsupported tag"),
+                }
+            }
+            ib.data_version = filter
+                .find_tag(DataVersionFilterTag::DataVersion as u32)?
+                .get_u32()?;
+            Ok(ib)
+        }
+
+        // True when the controller already holds this cluster's current version,
+        // so the read can omit its AttributeReportIb entries entirely.
+        pub fn is_current(&self, path: &GenericPath, data_version: u32) -> bool {
+            self.path.endpoint == path.endpoint
+                && self.path.cluster == path.cluster
+                && self.data_version == data_version
+        }
+    }
+
+    // Wildcard path expansion
+    //
+    // The spec lets a controller leave any of endpoint/cluster/attribute unset
+    // to mean "all of them". Expansion walks the live node and turns one such
+    // path into the concrete set it matches. A wildcard level is enumerated (and
+    // inaccessible branches quietly dropped). When endpoint and cluster are both
+    // concrete, an inaccessible cluster yields an `UnsupportedAccess` status and
+    // a concrete attribute missing from the cluster's attribute list yields an
+    // `UnsupportedAttribute` status, so the rest of the request still proceeds.
+    // Endpoints and clusters are not checked for existence on their own: a
+    // missing one shows up only as that `UnsupportedAttribute` status, and
+    // under a wildcard attribute it yields nothing.
+    pub trait PathExpander {
+        /// Endpoints present on the node, in ascending order.
+        fn endpoints(&self) -> Vec<u16>;
+        /// Clusters on an endpoint, in ascending order.
+        fn clusters(&self, endpoint: u16) -> Vec<u32>;
+        /// Attribute ids of a cluster, in ascending order.
+        fn attributes(&self, endpoint: u16, cluster: u32) -> Vec<u16>;
+        /// Whether the accessing fabric may see this cluster at all.
+        fn accessible(&self, _endpoint: u16, _cluster: u32) -> bool {
+            true
+        }
+    }
+
+    // A single outcome of expanding one level of a path: either a concrete path
+    // the handler should service, or a status terminating that branch.
+    pub enum Expanded {
+        Path(GenericPath),
+        Status(AttrStatus),
+    }
+
+    impl AttrPath {
+        // Enumerate this (possibly wildcard) attribute path against `model`,
+        // preserving the node's ordering.
+        pub fn expand<M: PathExpander>(&self, model: &M) -> Vec<Expanded> {
+            let mut out = Vec::new();
+            let wild_ep = self.path.endpoint.is_none();
+            let endpoints = match self.path.endpoint {
+                Some(ep) => vec![ep],
+                None => model.endpoints(),
+            };
+            for ep in endpoints {
+                let wild_cl = self.path.cluster.is_none();
+                let clusters = match self.path.cluster {
+                    Some(cl) => vec![cl],
+                    None => model.clusters(ep),
+                };
+                for cl in clusters {
+                    if !model.accessible(ep, cl) {
+                        // Unauthorized branches are silently skipped under a
+                        // wildcard; a concrete request for them is unsupported.
+                        if !wild_ep && !wild_cl {
+                            out.push(Expanded::Status(AttrStatus::new(
+                                &GenericPath {
+                                    endpoint: Some(ep),
+                                    cluster: Some(cl),
+                                    leaf: self.path.leaf,
+                                },
+                                IMStatusCode::UnsupportedAccess,
+                                0,
+                            )));
+                        }
+                        continue;
+                    }
+                    let attrs = model.attributes(ep, cl);
+                    match self.path.leaf {
+                        Some(attr) => {
+                            let p = GenericPath {
+                                endpoint: Some(ep),
+                                cluster: Some(cl),
+                                leaf: Some(attr),
+                            };
+                            if attrs.contains(&(attr as u16)) {
+                                out.push(Expanded::Path(p));
+                            } else if !wild_ep && !wild_cl {
+                                out.push(Expanded::Status(AttrStatus::new(
+                                    &p,
+                                    IMStatusCode::UnsupportedAttribute,
+                                    0,
+                                )));
+                            }
+                        }
+                        None => {
+                            for attr in attrs {
+                                out.push(Expanded::Path(GenericPath {
+                                    endpoint: Some(ep),
+                                    cluster: Some(cl),
+                                    leaf: Some(attr as u32),
+                                }));
+                            }
+                        }
+                    }
+                }
+            }
+            out
+        }
+    }
+
     // Command Path
     #[derive(Default, Debug, Copy, Clone)]
     pub struct CmdPath {
@@ -324,12 +504,46 @@ pub mod ib {
                     _ => error!("Unsupported tag"),
                 }
             }
+            // A command path must name a concrete command. The invoke handler
+            // is not wired to the wildcard expander, so accepting a `None` leaf
+            // here would let a wildcard command path parse and then silently
+            // match nothing rather than being enumerated. Reject it instead of
+            // loosening the parse ahead of the handler support.
             if ib.path.leaf.is_none() {
-                error!("Wildcard command parameter not supported");
-                Err(Error::CommandNotFound)
-            } else {
-                Ok(ib)
+                error!("Wildcard command path is not supported");
+                return Err(Error::Invalid);
             }
+            Ok(ib)
+        }
+
+        // Enumerate this (possibly wildcard) command path against `model`,
+        // preserving the node's ordering. Command ids themselves are not
+        // enumerated here; the leaf is copied unchanged into every result.
+        // `from_tlv` rejects a wildcard leaf, so a parsed path always names a
+        // concrete command.
+        pub fn expand<M: PathExpander>(&self, model: &M) -> Vec<GenericPath> {
+            let mut out = Vec::new();
+            let endpoints = match self.path.endpoint {
+                Some(ep) => vec![ep],
+                None => model.endpoints(),
+            };
+            for ep in endpoints {
+                let clusters = match self.path.cluster {
+                    Some(cl) => vec![cl],
+                    None => model.clusters(ep),
+                };
+                for cl in clusters {
+                    if !model.accessible(ep, cl) {
+                        continue;
+                    }
+                    out.push(GenericPath {
+                        endpoint: Some(ep),
+                        cluster: Some(cl),
+                        leaf: self.path.leaf,
+                    });
+                }
+            }
+            out
         }
     }
 
@@ -352,13 +566,103 @@ pub mod ib {
     // Report Data
     // TODO: Differs from spec
     pub enum ReportDataTag {
-        _SubscriptionId = 0,
+        SubscriptionId = 0,
         AttributeReportIb = 1,
-        _EventReport = 2,
-        _MoreChunkedMsgs = 3,
+        EventReport = 2,
+        MoreChunkedMsgs = 3,
         SupressResponse = 4,
     }
 
+    // Subscribe Request
+    //
+    // A controller asks to be pushed `ReportData` instead of polling. The
+    // min-interval floor and max-interval ceiling bound how often the server may
+    // report; the attribute paths (wildcards allowed, see `AttrPath::expand`)
+    // select what is watched.
+    #[derive(Debug)]
+    pub struct SubscribeReq {
+        pub keep_subscriptions: bool,
+        pub min_int_floor: u16,
+        pub max_int_ceil: u16,
+        pub attr_requests: Vec<AttrPath>,
+    }
+
+    #[derive(FromPrimitive)]
+    pub enum SubscribeReqTag {
+        KeepSubscriptions = 0,
+        MinIntervalFloor = 1,
+        MaxIntervalCeiling = 2,
+        AttrRequests = 3,
+        EventRequests = 4,
+        FabricFiltered = 5,
+    }
+
+    impl SubscribeReq {
+        pub fn from_tlv(req: &TLVElement) -> Result<Self, Error> {
+            let keep_subscriptions = req
+                .find_tag(SubscribeReqTag::KeepSubscriptions as u32)
+                .and_then(|t| t.get_bool())
+                .unwrap_or(false);
+            let min_int_floor = req
+                .find_tag(SubscribeReqTag::MinIntervalFloor as u32)?
+                .get_u16()?;
+            let max_int_ceil = req
+                .find_tag(SubscribeReqTag::MaxIntervalCeiling as u32)?
+                .get_u16()?;
+            let mut attr_requests = Vec::new();
+            if let Ok(list) = req.find_tag(SubscribeReqTag::AttrRequests as u32) {
+                if let Some(iter) = list.iter() {
+                    for a in iter {
+                        attr_requests.push(AttrPath::from_tlv(&a)?);
+                    }
+                }
+            }
+            Ok(Self {
+                keep_subscriptions,
+                min_int_floor,
+                max_int_ceil,
+                attr_requests,
+            })
+        }
+    }
+
+    // Subscribe Response: confirms the allocated subscription and the
+    // max-interval the server settled on.
+    #[derive(Debug, Clone, Copy)]
+    pub struct SubscribeResp {
+        subscription_id: u32,
+        max_int: u16,
+    }
+
+    pub enum SubscribeRespTag {
+        SubscriptionId = 0,
+        MaxInterval = 2,
+    }
+
+    impl SubscribeResp {
+        pub fn new(subscription_id: u32, max_int: u16) -> Self {
+            Self {
+                subscription_id,
+                max_int,
+            }
+        }
+    }
+
+    impl ToTLV for SubscribeResp {
+        fn to_tlv(&self, tw: &mut TLVWriter, tag_type: TagType) -> Result<(), Error> {
+            tw.put_start_struct(tag_type)?;
+            tw.put_u32(
+                TagType::Context(SubscribeRespTag::SubscriptionId as u8),
+                self.subscription_id,
+            )?;
+            tw.put_u16(
+                TagType::Context(SubscribeRespTag::MaxInterval as u8),
+                self.max_int,
+            )?;
+            tw.put_end_container()
+        }
+    }
+
     // Write Response
     pub enum WriteResponseTag {
         WriteResponses = 0,
@@ -0,0 +1,270 @@
+use super::common::SCStatusCodes;
+use crate::{
+    crypto::{self, CRYPTO_SYMMETRIC_KEY_SIZE_BYTES},
+    error::Error,
+};
+
+/// A ResumptionID is a 16-byte opaque handle exchanged at the end of a CASE
+/// handshake and presented in a later Sigma1 to request resumption.
+pub const RESUMPTION_ID_SIZE: usize = 16;
+pub type ResumptionId = [u8; RESUMPTION_ID_SIZE];
+
+// HKDF info strings that separate the resumption key schedule from the keys of
+// the original handshake, as per the CASE resumption procedure.
+const SESSION_RESUMPTION_KEYS_INFO: &[u8] = b"SessionResumptionKeys";
+const RESUME1_MIC_INFO: &[u8] = b"Resume1MICKey";
+const RESUME2_MIC_INFO: &[u8] = b"Resume2MICKey";
+
+/// The cached material a resumption is derived from: the shared secret from the
+/// original handshake plus the peer identity it was established with.
+pub struct ResumptionRecord {
+    shared_secret: [u8; CRYPTO_SYMMETRIC_KEY_SIZE_BYTES],
+    node_id: u64,
+    fabric_id: u64,
+    // When the record was created, as a Matter-epoch timestamp, for expiry.
+    created_at: u32,
+}
+
+impl ResumptionRecord {
+    pub fn new(
+        shared_secret: [u8; CRYPTO_SYMMETRIC_KEY_SIZE_BYTES],
+        node_id: u64,
+        fabric_id: u64,
+        created_at: u32,
+    ) -> Self {
+        Self {
+            shared_secret,
+            node_id,
+            fabric_id,
+            created_at,
+        }
+    }
+
+    pub fn node_id(&self) -> u64 {
+        self.node_id
+    }
+
+    pub fn fabric_id(&self) -> u64 {
+        self.fabric_id
+    }
+}
+
+/// The fresh directional session keys a successful resumption yields, skipping
+/// the Sigma1/2/3 elliptic-curve work entirely.
+pub struct ResumeKeys {
+    pub i2r: [u8; CRYPTO_SYMMETRIC_KEY_SIZE_BYTES],
+    pub r2i: [u8; CRYPTO_SYMMETRIC_KEY_SIZE_BYTES],
+}
+
+struct Entry {
+    id: ResumptionId,
+    record: ResumptionRecord,
+    // Monotonic access stamp used to evict the least-recently-used entry.
+    last_used: u64,
+}
+
+/// An LRU-bounded, time-expiring store of resumption records.
+///
+/// Capacity and expiry are configurable; when the cache is full the
+/// least-recently-used entry is evicted to make room, and an entry older than
+/// the expiry window is treated as absent (and purged) on lookup. The caller
+/// supplies the current Matter-epoch time, matching how the `cert` module
+/// injects time on clock-less nodes.
+pub struct ResumptionCache {
+    entries: Vec<Entry>,
+    capacity: usize,
+    expiry_secs: u32,
+    tick: u64,
+}
+
+impl ResumptionCache {
+    pub fn new(capacity: usize, expiry_secs: u32) -> Self {
+        Self {
+            entries: Vec::new(),
+            capacity: capacity.max(1),
+            expiry_secs,
+            tick: 0,
+        }
+    }
+
+    fn next_tick(&mut self) -> u64 {
+        self.tick += 1;
+        self.tick
+    }
+
+    fn is_expired(&self, record: &ResumptionRecord, now: u32) -> bool {
+        now.saturating_sub(record.created_at) > self.expiry_secs
+    }
+
+    /// Store a record under `id`, evicting the least-recently-used entry first
+    /// if the cache is already at capacity.
+    pub fn insert(&mut self, id: ResumptionId, record: ResumptionRecord) {
+        let last_used = self.next_tick();
+        if let Some(e) = self.entries.iter_mut().find(|e| e.id == id) {
+            e.record = record;
+            e.last_used = last_used;
+            return;
+        }
+        if self.entries.len() >= self.capacity {
+            if let Some((idx, _)) = self
+                .entries
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, e)| e.last_used)
+            {
+                self.entries.swap_remove(idx);
+            }
+        }
+        self.entries.push(Entry {
+            id,
+            record,
+            last_used,
+        });
+    }
+
+    // Locate a live record for `id`, purging it and returning None if it has
+    // expired. Updates the LRU stamp on a hit.
+    fn lookup(&mut self, id: &ResumptionId, now: u32) -> Option<&ResumptionRecord> {
+        let idx = self.entries.iter().position(|e| &e.id == id)?;
+        if self.is_expired(&self.entries[idx].record, now) {
+            self.entries.swap_remove(idx);
+            return None;
+        }
+        let tick = self.next_tick();
+        self.entries[idx].last_used = tick;
+        Some(&self.entries[idx].record)
+    }
+
+    /// Attempt to resume the session identified by `id` (responder side).
+    ///
+    /// On success, derives the fresh directional keys from the cached secret and
+    /// returns them with the Resume2 MIC to echo in the Sigma2Resume response.
+    /// When the id is unknown/expired or the initiator's Resume1 MIC does not
+    /// bind the presented randoms, returns the [`SCStatusCodes`] that signals the
+    /// caller to fall back to a full Sigma1/2/3 handshake.
+    pub fn resume(
+        &mut self,
+        id: &ResumptionId,
+        initiator_random: &[u8],
+        resume1_mic: &[u8],
+        responder_random: &[u8],
+        now: u32,
+    ) -> Result<(ResumeKeys, [u8; CRYPTO_SYMMETRIC_KEY_SIZE_BYTES * 2]), SCStatusCodes> {
+        let secret = match self.lookup(id, now) {
+            Some(record) => record.shared_secret,
+            None => return Err(SCStatusCodes::SessionNotFound),
+        };
+
+        // The initiator proves knowledge of the cached secret by binding the
+        // resumption id and its random into the Resume1 MIC.
+        let expect1 = resume_mic(&secret, id, initiator_random, RESUME1_MIC_INFO)
+            .map_err(|_| SCStatusCodes::InvalidParameter)?;
+        if !constant_time_eq(&expect1, resume1_mic) {
+            return Err(SCStatusCodes::InvalidParameter);
+        }
+
+        let keys = derive_resume_keys(&secret, id, initiator_random, responder_random)
+            .map_err(|_| SCStatusCodes::InvalidParameter)?;
+        let resume2_mic = resume_mic(&secret, id, responder_random, RESUME2_MIC_INFO)
+            .map_err(|_| SCStatusCodes::InvalidParameter)?;
+        Ok((keys, resume2_mic))
+    }
+}
+
+// Derive the directional session keys for a resumed session. The cached secret
+// is the IKM; both randoms salt the schedule so a resumption cannot reuse the
+// keys of the original handshake or of any other resumption.
+fn derive_resume_keys(
+    secret: &[u8; CRYPTO_SYMMETRIC_KEY_SIZE_BYTES],
+    id: &ResumptionId,
+    initiator_random: &[u8],
+    responder_random: &[u8],
+) -> Result<ResumeKeys, Error> {
+    let mut salt = Vec::with_capacity(id.len() + initiator_random.len() + responder_random.len());
+    salt.extend_from_slice(id);
+    salt.extend_from_slice(initiator_random);
+    salt.extend_from_slice(responder_random);
+
+    let mut out = [0u8; CRYPTO_SYMMETRIC_KEY_SIZE_BYTES * 2];
+    crypto::hkdf_sha256(&salt, secret, SESSION_RESUMPTION_KEYS_INFO, &mut out)?;
+
+    let mut keys = ResumeKeys {
+        i2r: [0; CRYPTO_SYMMETRIC_KEY_SIZE_BYTES],
+        r2i: [0; CRYPTO_SYMMETRIC_KEY_SIZE_BYTES],
+    };
+    keys.i2r
+        .copy_from_slice(&out[..CRYPTO_SYMMETRIC_KEY_SIZE_BYTES]);
+    keys.r2i
+        .copy_from_slice(&out[CRYPTO_SYMMETRIC_KEY_SIZE_BYTES..]);
+    Ok(keys)
+}
+
+// A resume MIC binds a random value to the resumption id under a key derived
+// from the cached secret with a MIC-specific info string.
+fn resume_mic(
+    secret: &[u8; CRYPTO_SYMMETRIC_KEY_SIZE_BYTES],
+    id: &ResumptionId,
+    random: &[u8],
+    info: &[u8],
+) -> Result<[u8; CRYPTO_SYMMETRIC_KEY_SIZE_BYTES * 2], Error> {
+    let mut mic_key = [0u8; CRYPTO_SYMMETRIC_KEY_SIZE_BYTES];
+    crypto::hkdf_sha256(id, secret, info, &mut mic_key)?;
+
+    let mut data = Vec::with_capacity(random.len() + id.len());
+    data.extend_from_slice(random);
+    data.extend_from_slice(id);
+
+    let mut mic = [0u8; CRYPTO_SYMMETRIC_KEY_SIZE_BYTES * 2];
+    crypto::hmac_sha256(&mic_key, &data, &mut mic)?;
+    Ok(mic)
+}
+
+// Data-independent byte comparison so a MIC mismatch cannot be timed; only a
+// length mismatch returns early.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut diff = 0u8;
+    for (x, y) in a.iter().zip(b.iter()) {
+        diff |= x ^ y;
+    }
+    diff == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(now: u32) -> ResumptionRecord {
+        ResumptionRecord::new([0x5a; CRYPTO_SYMMETRIC_KEY_SIZE_BYTES], 0x1122, 0x3344, now)
+    }
+
+    #[test]
+    fn lru_eviction_keeps_recently_used() {
+        let mut cache = ResumptionCache::new(2, 3600);
+        cache.insert([1; RESUMPTION_ID_SIZE], record(0));
+        cache.insert([2; RESUMPTION_ID_SIZE], record(0));
+        // Touch id 1 so id 2 becomes least-recently-used, then overflow.
+        assert!(cache.lookup(&[1; RESUMPTION_ID_SIZE], 0).is_some());
+        cache.insert([3; RESUMPTION_ID_SIZE], record(0));
+        assert!(cache.lookup(&[2; RESUMPTION_ID_SIZE], 0).is_none());
+        assert!(cache.lookup(&[1; RESUMPTION_ID_SIZE], 0).is_some());
+        assert!(cache.lookup(&[3; RESUMPTION_ID_SIZE], 0).is_some());
+    }
+
+    #[test]
+    fn expired_record_is_purged() {
+        let mut cache = ResumptionCache::new(4, 100);
+        cache.insert([7; RESUMPTION_ID_SIZE], record(1_000));
+        assert!(cache.lookup(&[7; RESUMPTION_ID_SIZE], 1_050).is_some());
+        assert!(cache.lookup(&[7; RESUMPTION_ID_SIZE], 1_200).is_none());
+    }
+
+    #[test]
+    fn unknown_id_falls_back() {
+        let mut cache = ResumptionCache::new(4, 100);
+        let r = cache.resume(&[9; RESUMPTION_ID_SIZE], &[0; 32], &[0; 32], &[0; 32], 0);
+        assert!(matches!(r, Err(SCStatusCodes::SessionNotFound)));
+    }
+}
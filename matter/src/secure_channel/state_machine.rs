@@ -0,0 +1,156 @@
+use super::common::{OpCode, SCStatusCodes};
+use crate::crypto::CRYPTO_SYMMETRIC_KEY_SIZE_BYTES;
+
+/// The ordered states a secure-channel session establishment passes through,
+/// covering both the PASE (PBKDF/Pake) and CASE (Sigma) exchanges.
+///
+/// Each message that crosses the wire - whether received from the peer or sent
+/// in response - advances the machine by exactly one state, so an out-of-order
+/// or replayed `OpCode` is rejected before any handler runs. The responder is
+/// the party modelled here, which is the role matter-rs plays during
+/// commissioning.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SecureChannelState {
+    Idle,
+    // PASE
+    PbkdfParamSent,
+    Pake1Received,
+    Pake2Sent,
+    Pake3Received,
+    // CASE
+    Sigma1Received,
+    Sigma2Sent,
+    Sigma3Received,
+    // Terminal
+    Established,
+}
+
+/// A misuse-resistant driver for a single session establishment.
+///
+/// Handlers feed every `OpCode` through [`SecureChannelDriver::advance`] before
+/// acting on it; the driver is the single authority on message ordering and on
+/// which [`SCStatusCodes`] to report when the ordering is violated. The derived
+/// session key is held back until the handshake reaches
+/// [`SecureChannelState::Established`].
+pub struct SecureChannelDriver {
+    state: SecureChannelState,
+    session_key: Option<[u8; CRYPTO_SYMMETRIC_KEY_SIZE_BYTES]>,
+}
+
+impl Default for SecureChannelDriver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SecureChannelDriver {
+    pub fn new() -> Self {
+        Self {
+            state: SecureChannelState::Idle,
+            session_key: None,
+        }
+    }
+
+    pub fn state(&self) -> SecureChannelState {
+        self.state
+    }
+
+    /// Validate `opcode` against the current state and, on success, move to the
+    /// next state. A message that is not the one expected next is rejected with
+    /// the status code a responder returns to the peer.
+    pub fn advance(&mut self, opcode: OpCode) -> Result<SecureChannelState, SCStatusCodes> {
+        use OpCode::*;
+        use SecureChannelState::*;
+
+        let next = match (self.state, opcode) {
+            // PASE
+            (Idle, PBKDFParamRequest) => PbkdfParamSent,
+            (PbkdfParamSent, PASEPake1) => Pake1Received,
+            (Pake1Received, PASEPake2) => Pake2Sent,
+            (Pake2Sent, PASEPake3) => Pake3Received,
+            // CASE
+            (Idle, CASESigma1) => Sigma1Received,
+            (Sigma1Received, CASESigma2) => Sigma2Sent,
+            (Sigma2Sent, CASESigma3) => Sigma3Received,
+            // Either handshake may abort at any point with a StatusReport.
+            (_, StatusReport) => return Err(SCStatusCodes::CloseSession),
+            _ => return Err(SCStatusCodes::InvalidParameter),
+        };
+        self.state = next;
+        Ok(next)
+    }
+
+    /// Record the derived symmetric key and move to [`Established`]. Only valid
+    /// from the final pre-established state of either handshake; calling it out
+    /// of order is a caller bug and reported as such.
+    ///
+    /// [`Established`]: SecureChannelState::Established
+    pub fn establish(
+        &mut self,
+        key: [u8; CRYPTO_SYMMETRIC_KEY_SIZE_BYTES],
+    ) -> Result<(), SCStatusCodes> {
+        match self.state {
+            SecureChannelState::Pake3Received | SecureChannelState::Sigma3Received => {
+                self.session_key = Some(key);
+                self.state = SecureChannelState::Established;
+                Ok(())
+            }
+            _ => Err(SCStatusCodes::InvalidParameter),
+        }
+    }
+
+    /// The derived session key, available only once the handshake has fully
+    /// completed. Returns `None` in every other state so a half-finished
+    /// exchange can never hand out key material.
+    pub fn session_key(&self) -> Option<&[u8; CRYPTO_SYMMETRIC_KEY_SIZE_BYTES]> {
+        match self.state {
+            SecureChannelState::Established => self.session_key.as_ref(),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pase_happy_path() {
+        let mut d = SecureChannelDriver::new();
+        // `SCStatusCodes` implements `PartialEq` but not `Debug`, so results
+        // carrying it are compared with `assert!(.. == ..)`.
+        assert!(d.advance(OpCode::PBKDFParamRequest) == Ok(SecureChannelState::PbkdfParamSent));
+        assert!(d.advance(OpCode::PASEPake1) == Ok(SecureChannelState::Pake1Received));
+        assert!(d.advance(OpCode::PASEPake2) == Ok(SecureChannelState::Pake2Sent));
+        assert!(d.advance(OpCode::PASEPake3) == Ok(SecureChannelState::Pake3Received));
+        assert!(d.session_key().is_none());
+        d.establish([0xab; CRYPTO_SYMMETRIC_KEY_SIZE_BYTES]).unwrap();
+        assert_eq!(d.state(), SecureChannelState::Established);
+        assert_eq!(d.session_key(), Some(&[0xab; CRYPTO_SYMMETRIC_KEY_SIZE_BYTES]));
+    }
+
+    #[test]
+    fn out_of_order_pake3_is_rejected() {
+        let mut d = SecureChannelDriver::new();
+        d.advance(OpCode::PBKDFParamRequest).unwrap();
+        assert!(d.advance(OpCode::PASEPake3) == Err(SCStatusCodes::InvalidParameter));
+    }
+
+    #[test]
+    fn case_happy_path() {
+        let mut d = SecureChannelDriver::new();
+        assert!(d.advance(OpCode::CASESigma1) == Ok(SecureChannelState::Sigma1Received));
+        assert!(d.advance(OpCode::CASESigma2) == Ok(SecureChannelState::Sigma2Sent));
+        assert!(d.advance(OpCode::CASESigma3) == Ok(SecureChannelState::Sigma3Received));
+        d.establish([0x11; CRYPTO_SYMMETRIC_KEY_SIZE_BYTES]).unwrap();
+        assert_eq!(d.session_key(), Some(&[0x11; CRYPTO_SYMMETRIC_KEY_SIZE_BYTES]));
+    }
+
+    #[test]
+    fn key_withheld_until_established() {
+        let mut d = SecureChannelDriver::new();
+        d.advance(OpCode::PBKDFParamRequest).unwrap();
+        assert!(d.establish([0; CRYPTO_SYMMETRIC_KEY_SIZE_BYTES]) == Err(SCStatusCodes::InvalidParameter));
+        assert!(d.session_key().is_none());
+    }
+}
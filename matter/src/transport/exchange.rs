@@ -1,7 +1,9 @@
 use boxslab::Slab;
+#[cfg(feature = "std")]
 use colored::*;
 use log::{error, info, trace};
 use std::fmt;
+use std::net::SocketAddr;
 use std::{any::Any, ops::DerefMut};
 
 use crate::error::Error;
@@ -15,7 +17,7 @@ use super::{
     mrp::ReliableMessage,
     packet::Packet,
     session::SessionHandle,
-    session::{Session, SessionMgr},
+    session::{Session, SessionMgr, MAX_SESSIONS},
 };
 
 pub struct ExchangeCtx<'a> {
@@ -41,7 +43,18 @@ impl Default for ExchangeRole {
     }
 }
 
-#[derive(Debug, Default)]
+/// A queued action on an exchange, drained asynchronously by
+/// `ExchangeMgr::service()`. Modeled on the session command channel: a message
+/// to put on the wire, or a request to tear the exchange down.
+pub enum ExchangeCmd {
+    Send { buf: Slab<PacketPool> },
+    Close,
+}
+
+// The number of commands that can be queued on a single exchange at a time.
+const MAX_EXCHANGE_CMDS: usize = 4;
+
+#[derive(Default)]
 pub struct Exchange {
     id: u16,
     sess_id: u16,
@@ -55,6 +68,9 @@ pub struct Exchange {
     // all 'exchanges'.
     data: Option<Box<dyn Any>>,
     mrp: ReliableMessage,
+    // Pending sends/closes to be drained by ExchangeMgr::service(). This
+    // decouples building a message from putting it on the wire.
+    cmd_queue: heapless::Deque<ExchangeCmd, MAX_EXCHANGE_CMDS>,
 }
 
 impl Exchange {
@@ -66,9 +82,24 @@ impl Exchange {
             user_cnt: 1,
             data: None,
             mrp: ReliableMessage::new(),
+            cmd_queue: heapless::Deque::new(),
         }
     }
 
+    /// Queue an encoded packet to be dispatched by `ExchangeMgr::service()`.
+    pub fn queue_send(&mut self, buf: Slab<PacketPool>) -> Result<(), Error> {
+        self.cmd_queue
+            .push_back(ExchangeCmd::Send { buf })
+            .map_err(|_| Error::NoSpace)
+    }
+
+    /// Queue a request to close this exchange once its queue has drained.
+    pub fn queue_close(&mut self) -> Result<(), Error> {
+        self.cmd_queue
+            .push_back(ExchangeCmd::Close)
+            .map_err(|_| Error::NoSpace)
+    }
+
     pub fn close(&mut self) {
         self.data = None;
         self.release();
@@ -115,9 +146,15 @@ impl Exchange {
 
     pub fn send(&mut self, proto_tx: &mut Packet, session: &mut Session) -> Result<(), Error> {
         trace!("payload: {:x?}", proto_tx.as_borrow_slice());
+        // The coloured decoration is only available when `std` (and hence a TTY)
+        // is present; on embedded targets we fall back to a plain label.
+        #[cfg(feature = "std")]
+        let sending = "Sending".blue();
+        #[cfg(not(feature = "std"))]
+        let sending = "Sending";
         info!(
             "{} with proto id: {} opcode: {}",
-            "Sending".blue(),
+            sending,
             proto_tx.get_proto_id(),
             proto_tx.get_proto_opcode(),
         );
@@ -163,13 +200,78 @@ pub fn get_complementary_role(is_initiator: bool) -> ExchangeRole {
     }
 }
 
-const MAX_EXCHANGES: usize = 8;
+/// The peer address a message is destined for.
+///
+/// The `Udp` variant wraps the session's `std::net::SocketAddr`, so this enum
+/// (and the exchange layer with it) depends on `std::net` in every build; there
+/// is no variant or feature that replaces it on targets without `std`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Addr {
+    /// A UDP peer, wrapping the session's `std::net::SocketAddr`.
+    Udp(SocketAddr),
+    /// The peer address is not (yet) known, e.g. before a session has been
+    /// associated with an address.
+    #[default]
+    Unknown,
+}
+
+impl From<SocketAddr> for Addr {
+    fn from(addr: SocketAddr) -> Self {
+        Addr::Udp(addr)
+    }
+}
 
+/// The sink through which an `ExchangeMgr` actually puts encoded packets on the
+/// wire. It is injected so that the manager can dispatch messages (e.g. the
+/// CLOSE report emitted while evicting a session) without owning the transport.
+pub trait TransportSink {
+    fn send(&mut self, addr: &Addr, buf: &[u8]) -> Result<(), Error>;
+}
+
+/// A sink that silently drops everything. This is the default so that an
+/// `ExchangeMgr` can be constructed before a transport has been wired up.
 #[derive(Default)]
+pub struct NullSink;
+
+impl TransportSink for NullSink {
+    fn send(&mut self, _addr: &Addr, _buf: &[u8]) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+const MAX_EXCHANGES: usize = 8;
+
+/// A point-in-time snapshot of `ExchangeMgr` resource occupancy.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ExchangeMgrReport {
+    pub active_exchanges: usize,
+    pub initiator_exchanges: usize,
+    pub responder_exchanges: usize,
+    pub max_exchanges: usize,
+    pub sessions_in_use: usize,
+    pub max_sessions: usize,
+    pub exchanges_with_pending_mrp: usize,
+    pub purgeable_exchanges: usize,
+}
+
 pub struct ExchangeMgr {
     // keys: exch-id
     exchanges: LinearMap<u16, Exchange, MAX_EXCHANGES>,
     sess_mgr: SessionMgr,
+    sink: Box<dyn TransportSink>,
+    // The next exchange id to hand out when we initiate an exchange
+    next_exch_id: u16,
+}
+
+impl Default for ExchangeMgr {
+    fn default() -> Self {
+        Self {
+            exchanges: Default::default(),
+            sess_mgr: Default::default(),
+            sink: Box::new(NullSink),
+            next_exch_id: 0,
+        }
+    }
 }
 
 pub const MAX_MRP_ENTRIES: usize = 4;
@@ -179,13 +281,115 @@ impl ExchangeMgr {
         Self {
             sess_mgr,
             exchanges: Default::default(),
+            sink: Box::new(NullSink),
+            next_exch_id: 0,
+        }
+    }
+
+    fn get_next_exch_id(&mut self) -> u16 {
+        loop {
+            let id = self.next_exch_id;
+            self.next_exch_id = self.next_exch_id.overflowing_add(1).0;
+            if !self.exchanges.contains_key(&id) {
+                break id;
+            }
         }
     }
 
+    /// Start a new exchange on an existing session as the given role (typically
+    /// `Initiator`). Returns the freshly allocated exchange id, which the caller
+    /// uses to queue sends and drive MRP retransmissions via `service()`.
+    pub fn initiate(&mut self, sess_id: u16, role: ExchangeRole) -> Result<u16, Error> {
+        let id = self.get_next_exch_id();
+        let e = Exchange::new(id, sess_id, role);
+        self.exchanges.insert(id, e).map_err(|_| Error::NoSpace)?;
+        Ok(id)
+    }
+
+    /// Pump the exchange manager: drain each exchange's queued sends through the
+    /// transport sink, fire MRP retransmissions whose timers have expired at
+    /// `now`, and flush any standalone ACKs that have become due.
+    pub fn service(&mut self, now: u64) -> Result<(), Error> {
+        let mut to_close: LinearMap<u16, (), MAX_EXCHANGES> = LinearMap::new();
+        // Split the borrows so we can resolve each exchange's peer address from
+        // the session manager while draining its send queue through the sink.
+        let Self {
+            exchanges,
+            sess_mgr,
+            sink,
+            ..
+        } = self;
+        for (exch_id, exchange) in exchanges.iter_mut() {
+            let addr = sess_mgr
+                .get_with_id(exchange.sess_id)
+                .map(|s| Addr::from(s.get_peer_addr()))
+                .unwrap_or_default();
+            while let Some(cmd) = exchange.cmd_queue.pop_front() {
+                match cmd {
+                    ExchangeCmd::Send { buf } => {
+                        sink.send(&addr, buf.as_borrow_slice())?;
+                    }
+                    ExchangeCmd::Close => {
+                        let _ = to_close.insert(*exch_id, ());
+                    }
+                }
+            }
+            // Re-arm any MRP retransmission whose timer has expired.
+            if let Some(buf) = exchange.mrp.retrans_if_expired(now)? {
+                sink.send(&addr, buf.as_borrow_slice())?;
+            }
+            // Flush a pending standalone ACK, if one is ready.
+            if exchange.mrp.is_ack_ready() {
+                let mut ack = Slab::<PacketPool>::new(Packet::new_tx()?).ok_or(Error::NoSpace)?;
+                secure_channel::common::create_mrp_standalone_ack(&mut ack);
+                exchange.mrp.pre_send(&mut ack)?;
+                sink.send(&addr, ack.as_borrow_slice())?;
+            }
+        }
+        for (exch_id, _) in to_close.iter() {
+            if let Some(e) = self.exchanges.get_mut(exch_id) {
+                e.close();
+            }
+        }
+        self.purge();
+        Ok(())
+    }
+
+    /// Install the transport sink through which outgoing packets are dispatched.
+    pub fn set_sink(&mut self, sink: Box<dyn TransportSink>) {
+        self.sink = sink;
+    }
+
     pub fn get_sess_mgr(&mut self) -> &mut SessionMgr {
         &mut self.sess_mgr
     }
 
+    /// A cheap snapshot of exchange/session resource usage, suitable for
+    /// periodic logging on embedded targets where the tables are fixed-size and
+    /// operators need to see how close they are to exhaustion.
+    pub fn report(&self) -> ExchangeMgrReport {
+        let mut report = ExchangeMgrReport {
+            max_exchanges: MAX_EXCHANGES,
+            max_sessions: MAX_SESSIONS,
+            ..Default::default()
+        };
+        for (_, exchange) in self.exchanges.iter() {
+            report.active_exchanges += 1;
+            match exchange.get_role() {
+                ExchangeRole::Initiator => report.initiator_exchanges += 1,
+                ExchangeRole::Responder => report.responder_exchanges += 1,
+            }
+            if !exchange.mrp.is_empty() {
+                report.exchanges_with_pending_mrp += 1;
+            }
+            if exchange.is_purgeable() {
+                report.purgeable_exchanges += 1;
+            }
+        }
+        report.sessions_in_use = self.sess_mgr.get_session_count();
+        report
+    }
+
     pub fn _get_with_id(
         exchanges: &mut LinearMap<u16, Exchange, MAX_EXCHANGES>,
         exch_id: u16,
@@ -325,6 +529,9 @@ impl ExchangeMgr {
         )?;
 
         let sess_id = session.get_local_sess_id();
+        // Remember where this session's peer lives so the CLOSE report is
+        // dispatched to a routable address rather than a placeholder.
+        let peer_addr = Addr::from(session.get_peer_addr());
 
         if let Some((_, exchange)) =
             self.exchanges
@@ -335,20 +542,21 @@ impl ExchangeMgr {
             // Should this be done for all exchanges?
             error!("Sending Close Session");
             exchange.send(&mut tx, session)?;
-            // TODO: This wouldn't actually send it out, because 'transport' isn't owned yet.
+            // Hand the freshly encoded CLOSE report to the transport sink so it
+            // actually leaves the node before the session is torn down.
+            self.sink.send(&peer_addr, tx.as_borrow_slice())?;
         }
 
-        let remove_exchanges: Vec<u16> = self
-            .exchanges
-            .iter()
-            .filter_map(|(eid, e)| {
-                if e.sess_id == sess_id {
-                    Some(*eid)
-                } else {
-                    None
-                }
-            })
-            .collect();
+        // A fixed-capacity list keeps eviction allocation-free so that the same
+        // path works under `no_std`. There can never be more exchanges for a
+        // session than the exchange table can hold.
+        let mut remove_exchanges: heapless::Vec<u16, MAX_EXCHANGES> = heapless::Vec::new();
+        for (eid, e) in self.exchanges.iter() {
+            if e.sess_id == sess_id {
+                // The capacity matches the exchange table, so this cannot overflow
+                remove_exchanges.push(*eid).map_err(|_| Error::NoSpace)?;
+            }
+        }
         info!(
             "Terminating the following exchanges: {:?}",
             remove_exchanges
@@ -398,7 +606,23 @@ mod tests {
         transport::session::{CloneData, SessionMgr, SessionMode, MAX_SESSIONS},
     };
 
-    use super::{ExchangeMgr, ExchangeRole};
+    use super::{Addr, ExchangeMgr, ExchangeRole, TransportSink};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// An in-memory transport sink that records every encoded packet handed to
+    /// it, so tests can assert what actually went out on the wire.
+    #[derive(Default, Clone)]
+    struct LoopbackSink {
+        queue: Rc<RefCell<Vec<Vec<u8>>>>,
+    }
+
+    impl TransportSink for LoopbackSink {
+        fn send(&mut self, _addr: &Addr, buf: &[u8]) -> Result<(), Error> {
+            self.queue.borrow_mut().push(buf.to_vec());
+            Ok(())
+        }
+    }
 
     #[test]
     fn test_purge() {
@@ -473,6 +697,8 @@ mod tests {
     fn test_sess_evict() {
         let sess_mgr = SessionMgr::new();
         let mut mgr = ExchangeMgr::new(sess_mgr);
+        let sink = LoopbackSink::default();
+        mgr.set_sink(Box::new(sink.clone()));
 
         fill_sessions(&mut mgr, MAX_SESSIONS + 1);
         // Sessions are now full from local session id 1 to 16
@@ -528,5 +754,9 @@ mod tests {
             }
         }
         //        println!("Session mgr {}", mgr.sess_mgr);
+
+        // Only sessions 2 and 3 had live exchanges, so exactly two CloseSession
+        // reports should have been dispatched through the transport sink.
+        assert_eq!(sink.queue.borrow().len(), 2);
     }
 }
@@ -4,7 +4,11 @@ use matter::{
         Quality,
     },
     error::Error,
-    interaction_model::{command::CommandReq, core::IMStatusCode, messages::ib},
+    interaction_model::{
+        command::CommandReq,
+        core::IMStatusCode,
+        messages::{ib, GenericPath},
+    },
     tlv::{TLVWriter, TagType, ToTLV},
 };
 use num_derive::FromPrimitive;
@@ -20,6 +24,10 @@ pub enum Commands {
 pub struct EchoCluster {
     base: Cluster,
     multiplier: u8,
+    // Per-cluster data version, advanced on every observable mutation. Reads
+    // and subscriptions carrying a `DataVersionFilter` use it to skip clusters
+    // that have not changed since the requester last saw them.
+    data_ver: u32,
 }
 
 #[derive(FromPrimitive)]
@@ -79,6 +87,10 @@ impl ClusterType for EchoCluster {
                     EncodeValue::Closure(&cmd_data),
                 ));
                 let _ = invoke_resp.to_tlv(cmd_req.resp, TagType::Anonymous);
+                // An EchoReq mutates observable cluster state, so advance the
+                // data version; dataver-filtered reads/subscriptions use this to
+                // tell whether a cluster changed since the requester last saw it.
+                self.bump_dataver();
                 cmd_req.trans.complete();
             }
             _ => {
@@ -94,6 +106,7 @@ impl EchoCluster {
         let mut c = Box::new(Self {
             base: Cluster::new(ID)?,
             multiplier,
+            data_ver: 0,
         });
         c.base.add_attribute(Attribute::new(
             Attributes::Att1 as u16,
@@ -121,4 +134,21 @@ impl EchoCluster {
         )?)?;
         Ok(c)
     }
+
+    /// The cluster's current data version, emitted alongside attribute reports.
+    pub fn get_dataver(&self) -> u32 {
+        self.data_ver
+    }
+
+    /// Advance the data version after a mutation. The value is opaque, so a
+    /// wrapping increment is sufficient to signal "something changed".
+    pub fn bump_dataver(&mut self) {
+        self.data_ver = self.data_ver.wrapping_add(1);
+    }
+
+    /// Whether a dataver-filtered read of `path` may omit this cluster, i.e.
+    /// the requester already holds the current data version.
+    pub fn dataver_is_current(&self, filter: &ib::DataVersionFilter, path: &GenericPath) -> bool {
+        filter.is_current(path, self.data_ver)
+    }
 }
@@ -0,0 +1,131 @@
+//! Constant-time reduction of the SPAKE2+ PBKDF2 outputs modulo the P-256
+//! group order.
+//!
+//! `w0s`/`w1s` are 320-bit values that must be reduced to a scalar in `[0, n)`.
+//! OpenSSL's `checked_rem` and the generic big-integer `%` are not guaranteed
+//! constant-time, which would leak timing on the password-equivalent secrets.
+//! This module implements Barrett reduction over fixed-width `u64` limbs: every
+//! multiply/subtract step processes the full limb count regardless of operand
+//! magnitude, and the final conditional subtractions of `n` are selected with a
+//! bit mask rather than a branch, so the running time is independent of the
+//! secret value.
+
+// The P-256 group order `n` and the precomputed Barrett factor
+// `mu = floor(2^512 / n)`, both as little-endian `u64` limbs.
+const N: [u64; 4] = [
+    0xf3b9_cac2_fc63_2551,
+    0xbce6_faad_a717_9e84,
+    0xffff_ffff_ffff_ffff,
+    0xffff_ffff_0000_0000,
+];
+const MU: [u64; 5] = [
+    0x012f_fd85_eedf_9bfe,
+    0x4319_0552_df1a_6c21,
+    0xffff_fffe_ffff_ffff,
+    0x0000_0000_ffff_ffff,
+    0x0000_0000_0000_0001,
+];
+
+// Schoolbook multiply of two little-endian limb slices into `out`, which must
+// be at least `a.len() + b.len()` limbs. Runs over every limb unconditionally.
+fn mul(a: &[u64], b: &[u64], out: &mut [u64]) {
+    for o in out.iter_mut() {
+        *o = 0;
+    }
+    for (i, &ai) in a.iter().enumerate() {
+        let mut carry: u128 = 0;
+        for (j, &bj) in b.iter().enumerate() {
+            let t = ai as u128 * bj as u128 + out[i + j] as u128 + carry;
+            out[i + j] = t as u64;
+            carry = t >> 64;
+        }
+        out[i + b.len()] = out[i + b.len()].wrapping_add(carry as u64);
+    }
+}
+
+// `out = a - b` over equal-length limb slices, returning the final borrow
+// (0 or 1). The borrow is derived from the wrap-around bit, never a branch.
+fn sub(a: &[u64], b: &[u64], out: &mut [u64]) -> u64 {
+    let mut borrow: u128 = 0;
+    for i in 0..a.len() {
+        let t = (a[i] as u128).wrapping_sub(b[i] as u128 + borrow);
+        out[i] = t as u64;
+        borrow = (t >> 64) & 1;
+    }
+    borrow as u64
+}
+
+/// Reduce a big-endian scalar of up to 40 bytes modulo `n`, returning the
+/// 32-byte big-endian canonical representative. Constant-time in the value.
+pub fn reduce(input_be: &[u8]) -> [u8; 32] {
+    // Load the input into six little-endian limbs (384-bit capacity).
+    let mut x = [0u64; 6];
+    for (i, &byte) in input_be.iter().rev().enumerate() {
+        x[i / 8] |= (byte as u64) << ((i % 8) * 8);
+    }
+
+    // q = floor(x * mu / 2^512): the product is 11 limbs; the >> 512 keeps
+    // limbs 8.. (three limbs).
+    let mut prod = [0u64; 11];
+    mul(&x, &MU, &mut prod);
+    let q = [prod[8], prod[9], prod[10]];
+
+    // qn = q * n, truncated to the low six limbs (the high limbs cancel).
+    let mut qn_full = [0u64; 7];
+    mul(&q, &N, &mut qn_full);
+    let qn = [
+        qn_full[0], qn_full[1], qn_full[2], qn_full[3], qn_full[4], qn_full[5],
+    ];
+
+    // r = x - qn; Barrett leaves r < 3n, so at most two conditional
+    // subtractions of n bring it into range.
+    let mut r = [0u64; 6];
+    sub(&x, &qn, &mut r);
+
+    let n6 = [N[0], N[1], N[2], N[3], 0, 0];
+    for _ in 0..2 {
+        let mut t = [0u64; 6];
+        let borrow = sub(&r, &n6, &mut t);
+        // borrow == 0 means r >= n, so select t; otherwise keep r.
+        let mask = 0u64.wrapping_sub(1 - borrow);
+        for i in 0..6 {
+            r[i] = (r[i] & !mask) | (t[i] & mask);
+        }
+    }
+
+    // Serialize the low four limbs back to big-endian.
+    let mut out = [0u8; 32];
+    for i in 0..4 {
+        out[(3 - i) * 8..(3 - i) * 8 + 8].copy_from_slice(&r[i].to_be_bytes());
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::reduce;
+
+    #[test]
+    fn test_reduce_wide() {
+        // A 320-bit value and its residue mod n, computed independently.
+        let x: [u8; 40] = [
+            0x00, 0x00, 0x00, 0x01, 0x23, 0x45, 0x67, 0x89, 0xab, 0xcd, 0xef, 0xfe, 0xdc, 0xba,
+            0x98, 0x76, 0x54, 0x32, 0x10, 0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88,
+            0x99, 0x00, 0xaa, 0xbb, 0xcc, 0xdd, 0xee, 0xff, 0x00, 0x11, 0x22, 0x33,
+        ];
+        let expected: [u8; 32] = [
+            0xcf, 0x13, 0x57, 0x87, 0xb9, 0x75, 0x30, 0xec, 0x54, 0x32, 0x10, 0x00, 0x5d, 0x79,
+            0xd5, 0x32, 0x9c, 0x49, 0x95, 0x23, 0x2a, 0x9b, 0xd6, 0x71, 0x5e, 0xaf, 0x74, 0x32,
+            0xec, 0xc6, 0x6d, 0x89,
+        ];
+        assert_eq!(reduce(&x), expected);
+    }
+
+    #[test]
+    fn test_reduce_already_small() {
+        // A value already below n is returned unchanged.
+        let mut v = [0u8; 32];
+        v[31] = 0x2a;
+        assert_eq!(reduce(&v), v);
+    }
+}
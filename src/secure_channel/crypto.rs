@@ -0,0 +1,209 @@
+use crate::error::Error;
+
+// The size of a SPAKE2+ scalar (w0/w1) after PBKDF2, as per the Matter spec.
+pub const CRYPTO_GROUP_SIZE_BYTES: usize = 32;
+pub const CRYPTO_W_SIZE_BYTES: usize = CRYPTO_GROUP_SIZE_BYTES + 8;
+// An uncompressed P-256 point (0x04 || X || Y).
+pub const CRYPTO_PUBLIC_KEY_SIZE_BYTES: usize = 65;
+
+/// Backend-agnostic SPAKE2+ primitive surface used by the PASE handlers.
+///
+/// The scalars `w0`/`w1` cross this boundary as big-endian byte strings, so a
+/// backend (OpenSSL, a pure-Rust implementation, or a hardware/PSA engine) can
+/// be plugged in without the PASE logic depending on its internal
+/// representation. The group-element arithmetic stays with the concrete backend.
+pub trait CryptoUtils {
+    fn new() -> Result<Self, Error>
+    where
+        Self: Sized;
+
+    // Reduce the PBKDF2 outputs modulo the curve order: w0 = w0s mod p.
+    fn set_w0_from_w0s(&mut self, w0s: &[u8]) -> Result<(), Error>;
+    fn set_w1_from_w1s(&mut self, w1s: &[u8]) -> Result<(), Error>;
+
+    // Set the scalars directly (used primarily by the test vectors).
+    fn set_w0(&mut self, w0: &[u8]) -> Result<(), Error>;
+    fn set_w1(&mut self, w1: &[u8]) -> Result<(), Error>;
+}
+
+/// The active `CryptoUtils` backend, selected at compile time. The pure-Rust
+/// backend is used for `no_std`/embedded builds behind the `crypto_rustcrypto`
+/// feature; otherwise the OpenSSL backend is linked. Code that names
+/// `CryptoBackend` rather than a concrete backend stays backend-agnostic.
+#[cfg(feature = "crypto_rustcrypto")]
+pub use super::crypto_rustcrypto::CryptoRustCrypto as CryptoBackend;
+#[cfg(not(feature = "crypto_rustcrypto"))]
+pub use super::crypto_openssl::CryptoOpenSSL as CryptoBackend;
+
+// An uncompressed P-256 point (0x04 || X || Y).
+pub const P256_POINT_SIZE: usize = CRYPTO_PUBLIC_KEY_SIZE_BYTES;
+// AES-128-CCM authentication tag length used throughout Matter.
+pub const AEAD_MIC_SIZE: usize = 16;
+
+/// The full set of symmetric and asymmetric primitives the secure channel (PASE
+/// and, once present, CASE) relies on, abstracted behind one trait so a build
+/// can route them to a software implementation or to a PSA/CryptoCell-style
+/// hardware accelerator selected by Cargo feature.
+///
+/// Methods are associated functions (no receiver) because the primitives are
+/// stateless; a stateful hardware session is managed inside the backend. All
+/// scalars and points cross the boundary as fixed-width big-endian/SEC1 byte
+/// strings so callers never depend on a backend's internal representation.
+pub trait Crypto {
+    // Fill `out` with cryptographically secure random bytes. Routed through the
+    // backend so an embedded build draws from its hardware DRBG rather than a
+    // host RNG that may be unavailable under `no_std`.
+    fn rand_fill(out: &mut [u8]);
+
+    fn sha256(data: &[u8]) -> [u8; 32];
+    fn hkdf_expand(key: &[u8], info: &[u8], out: &mut [u8]) -> Result<(), Error>;
+    fn hmac(key: &[u8], data: &[u8], out: &mut [u8]) -> Result<(), Error>;
+    fn pbkdf2(pw: &[u8], salt: &[u8], iter: u32, out: &mut [u8]);
+
+    // P-256: `scalar * G` and `scalar * point`, both yielding an uncompressed
+    // SEC1 encoding. Inputs are 32-byte big-endian scalars / 65-byte points.
+    fn p256_mul_gen(scalar: &[u8; CRYPTO_GROUP_SIZE_BYTES])
+        -> Result<[u8; P256_POINT_SIZE], Error>;
+    fn p256_mul(
+        point: &[u8; P256_POINT_SIZE],
+        scalar: &[u8; CRYPTO_GROUP_SIZE_BYTES],
+    ) -> Result<[u8; P256_POINT_SIZE], Error>;
+
+    // AES-128-CCM AEAD over an in-place buffer, producing/consuming a trailing
+    // `AEAD_MIC_SIZE` tag.
+    fn aead_encrypt(
+        key: &[u8],
+        nonce: &[u8],
+        aad: &[u8],
+        data: &mut [u8],
+        tag: &mut [u8; AEAD_MIC_SIZE],
+    ) -> Result<(), Error>;
+    fn aead_decrypt(
+        key: &[u8],
+        nonce: &[u8],
+        aad: &[u8],
+        data: &mut [u8],
+        tag: &[u8; AEAD_MIC_SIZE],
+    ) -> Result<(), Error>;
+}
+
+/// The default, pure-Rust `Crypto` backend wrapping the RustCrypto crates. It
+/// is always available and is the only `Crypto` implementation in this crate.
+pub struct SoftwareCrypto;
+
+impl Crypto for SoftwareCrypto {
+    fn rand_fill(out: &mut [u8]) {
+        use p256::elliptic_curve::rand_core::{OsRng, RngCore};
+        OsRng.fill_bytes(out);
+    }
+
+    fn sha256(data: &[u8]) -> [u8; 32] {
+        use sha2::{Digest, Sha256};
+        let d = Sha256::digest(data);
+        let mut out = [0; 32];
+        out.copy_from_slice(d.as_slice());
+        out
+    }
+
+    fn hkdf_expand(key: &[u8], info: &[u8], out: &mut [u8]) -> Result<(), Error> {
+        use hkdf::Hkdf;
+        use sha2::Sha256;
+        Hkdf::<Sha256>::new(None, key)
+            .expand(info, out)
+            .map_err(|_| Error::NoSpace)
+    }
+
+    fn hmac(key: &[u8], data: &[u8], out: &mut [u8]) -> Result<(), Error> {
+        use hmac::{Hmac, Mac, NewMac};
+        use sha2::Sha256;
+        let mut mac = Hmac::<Sha256>::new_from_slice(key).map_err(|_| Error::InvalidKeyLength)?;
+        mac.update(data);
+        let r = mac.finalize().into_bytes();
+        if r.len() != out.len() {
+            return Err(Error::NoSpace);
+        }
+        out.copy_from_slice(r.as_slice());
+        Ok(())
+    }
+
+    fn pbkdf2(pw: &[u8], salt: &[u8], iter: u32, out: &mut [u8]) {
+        use hmac::Hmac;
+        use sha2::Sha256;
+        pbkdf2::pbkdf2::<Hmac<Sha256>>(pw, salt, iter, out);
+    }
+
+    fn p256_mul_gen(
+        scalar: &[u8; CRYPTO_GROUP_SIZE_BYTES],
+    ) -> Result<[u8; P256_POINT_SIZE], Error> {
+        use elliptic_curve::ops::Reduce;
+        use elliptic_curve::sec1::ToEncodedPoint;
+        use p256::{ProjectivePoint, Scalar, U256};
+        let s = Scalar::reduce(U256::from_be_slice(scalar));
+        let p = ProjectivePoint::GENERATOR * s;
+        let enc = p.to_affine().to_encoded_point(false);
+        let mut out = [0; P256_POINT_SIZE];
+        out.copy_from_slice(enc.as_bytes());
+        Ok(out)
+    }
+
+    fn p256_mul(
+        point: &[u8; P256_POINT_SIZE],
+        scalar: &[u8; CRYPTO_GROUP_SIZE_BYTES],
+    ) -> Result<[u8; P256_POINT_SIZE], Error> {
+        use elliptic_curve::ops::Reduce;
+        use elliptic_curve::sec1::{FromEncodedPoint, ToEncodedPoint};
+        use p256::{EncodedPoint, ProjectivePoint, Scalar, U256};
+        let ep = EncodedPoint::from_bytes(point).map_err(|_| Error::Invalid)?;
+        let p: ProjectivePoint =
+            Option::from(ProjectivePoint::from_encoded_point(&ep)).ok_or(Error::Invalid)?;
+        let s = Scalar::reduce(U256::from_be_slice(scalar));
+        let enc = (p * s).to_affine().to_encoded_point(false);
+        let mut out = [0; P256_POINT_SIZE];
+        out.copy_from_slice(enc.as_bytes());
+        Ok(out)
+    }
+
+    fn aead_encrypt(
+        key: &[u8],
+        nonce: &[u8],
+        aad: &[u8],
+        data: &mut [u8],
+        tag: &mut [u8; AEAD_MIC_SIZE],
+    ) -> Result<(), Error> {
+        use aes::Aes128;
+        use ccm::aead::{AeadInPlace, NewAead};
+        use ccm::consts::{U13, U16};
+        use ccm::Ccm;
+        type AesCcm = Ccm<Aes128, U16, U13>;
+        let cipher = AesCcm::new_from_slice(key).map_err(|_| Error::InvalidKeyLength)?;
+        let t = cipher
+            .encrypt_in_place_detached(nonce.into(), aad, data)
+            .map_err(|_| Error::Invalid)?;
+        tag.copy_from_slice(t.as_slice());
+        Ok(())
+    }
+
+    fn aead_decrypt(
+        key: &[u8],
+        nonce: &[u8],
+        aad: &[u8],
+        data: &mut [u8],
+        tag: &[u8; AEAD_MIC_SIZE],
+    ) -> Result<(), Error> {
+        use aes::Aes128;
+        use ccm::aead::{AeadInPlace, NewAead};
+        use ccm::consts::{U13, U16};
+        use ccm::Ccm;
+        type AesCcm = Ccm<Aes128, U16, U13>;
+        let cipher = AesCcm::new_from_slice(key).map_err(|_| Error::InvalidKeyLength)?;
+        cipher
+            .decrypt_in_place_detached(nonce.into(), aad, data, tag.into())
+            .map_err(|_| Error::Invalid)
+    }
+}
+
+/// The `Crypto` backend selected for this build. Only the pure-Rust
+/// [`SoftwareCrypto`] backend exists today; a hardware/PSA backend would be
+/// wired in here behind its own feature once the module is added, rather than
+/// referencing a module that is not present in the tree.
+pub use SoftwareCrypto as DefaultCrypto;
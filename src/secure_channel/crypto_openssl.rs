@@ -59,42 +59,44 @@ impl CryptoUtils for CryptoOpenSSL {
             N,
         })
     }
-}
 
-impl CryptoOpenSSL {
     // Computes w0 from w0s respectively
-    pub fn set_w0_from_w0s(&mut self, w0s: &[u8]) -> Result<(), Error> {
+    fn set_w0_from_w0s(&mut self, w0s: &[u8]) -> Result<(), Error> {
         // From the Matter Spec,
         //         w0 = w0s mod p
         //   where p is the order of the curve
-
-        let w0s = BigNum::from_slice(w0s)?;
-        self.w0.checked_rem(&w0s, &self.order, &mut self.bn_ctx)?;
+        //
+        // The reduction goes through the constant-time Barrett path rather than
+        // OpenSSL's `checked_rem`, which is not guaranteed constant-time and
+        // would leak timing on the password-equivalent secret.
+        let w0 = super::barrett::reduce(w0s);
+        self.w0 = BigNum::from_slice(&w0)?;
 
         Ok(())
     }
 
-    pub fn set_w1_from_w1s(&mut self, w1s: &[u8]) -> Result<(), Error> {
+    fn set_w1_from_w1s(&mut self, w1s: &[u8]) -> Result<(), Error> {
         // From the Matter Spec,
-        //         w0 = w0s mod p
-        //   where p is the order of the curve
-
-        let w1s = BigNum::from_slice(w1s)?;
-        self.w1.checked_rem(&w1s, &self.order, &mut self.bn_ctx)?;
+        //         w1 = w1s mod p
+        //   where p is the order of the curve (see `set_w0_from_w0s`).
+        let w1 = super::barrett::reduce(w1s);
+        self.w1 = BigNum::from_slice(&w1)?;
 
         Ok(())
     }
 
-    pub fn set_w0(&mut self, w0: &[u8]) -> Result<(), Error> {
+    fn set_w0(&mut self, w0: &[u8]) -> Result<(), Error> {
         self.w0 = BigNum::from_slice(w0)?;
         Ok(())
     }
 
-    pub fn set_w1(&mut self, w1: &[u8]) -> Result<(), Error> {
+    fn set_w1(&mut self, w1: &[u8]) -> Result<(), Error> {
         self.w1 = BigNum::from_slice(w1)?;
         Ok(())
     }
+}
 
+impl CryptoOpenSSL {
     #[allow(non_snake_case)]
     fn get_L(&mut self, w0w1s: &[u8], order: &BigNum) -> Result<EcPoint, Error> {
         // From the Matter spec,
@@ -0,0 +1,207 @@
+use crate::error::Error;
+
+use super::crypto::CryptoUtils;
+use elliptic_curve::group::GroupEncoding;
+use elliptic_curve::ops::Reduce;
+use elliptic_curve::sec1::FromEncodedPoint;
+use p256::{EncodedPoint, ProjectivePoint, Scalar, U256};
+
+// The Matter-defined SPAKE2+ points M and N, as uncompressed SEC1 encodings.
+// These are identical to the constants the OpenSSL backend loads, so both
+// backends agree on the group elements.
+const MATTER_M_BIN: [u8; 65] = [
+    0x04, 0x88, 0x6e, 0x2f, 0x97, 0xac, 0xe4, 0x6e, 0x55, 0xba, 0x9d, 0xd7, 0x24, 0x25, 0x79, 0xf2,
+    0x99, 0x3b, 0x64, 0xe1, 0x6e, 0xf3, 0xdc, 0xab, 0x95, 0xaf, 0xd4, 0x97, 0x33, 0x3d, 0x8f, 0xa1,
+    0x2f, 0x5f, 0xf3, 0x55, 0x16, 0x3e, 0x43, 0xce, 0x22, 0x4e, 0x0b, 0x0e, 0x65, 0xff, 0x02, 0xac,
+    0x8e, 0x5c, 0x7b, 0xe0, 0x94, 0x19, 0xc7, 0x85, 0xe0, 0xca, 0x54, 0x7d, 0x55, 0xa1, 0x2e, 0x2d,
+    0x20,
+];
+const MATTER_N_BIN: [u8; 65] = [
+    0x04, 0xd8, 0xbb, 0xd6, 0xc6, 0x39, 0xc6, 0x29, 0x37, 0xb0, 0x4d, 0x99, 0x7f, 0x38, 0xc3, 0x77,
+    0x07, 0x19, 0xc6, 0x29, 0xd7, 0x01, 0x4d, 0x49, 0xa2, 0x4b, 0x4f, 0x98, 0xba, 0xa1, 0x29, 0x2b,
+    0x49, 0x07, 0xd6, 0x0a, 0xa6, 0xbf, 0xad, 0xe4, 0x50, 0x08, 0xa6, 0x36, 0x33, 0x7f, 0x51, 0x68,
+    0xc6, 0x4d, 0x9b, 0xd3, 0x60, 0x34, 0x80, 0x8c, 0xd5, 0x64, 0x49, 0x0b, 0x1e, 0x65, 0x6e, 0xdb,
+    0xe7,
+];
+
+fn point_from_sec1(bin: &[u8]) -> Result<ProjectivePoint, Error> {
+    let ep = EncodedPoint::from_bytes(bin).map_err(|_| Error::Invalid)?;
+    Option::from(ProjectivePoint::from_encoded_point(&ep)).ok_or(Error::Invalid)
+}
+
+// Reduce a big-endian scalar slice modulo the group order into a `Scalar`.
+// `w0s`/`w1s` are 40 bytes (wider than n), so reduce the 320-bit value; the
+// 32-byte `set_w0`/`set_w1` path is already in range and is taken verbatim.
+fn scalar_mod_order(bytes: &[u8]) -> Result<Scalar, Error> {
+    if bytes.len() == 32 {
+        let arr: [u8; 32] = bytes.try_into().map_err(|_| Error::Invalid)?;
+        return Ok(Scalar::reduce(U256::from_be_slice(&arr)));
+    }
+    // Wider than n: reduce with the constant-time Barrett path to avoid
+    // leaking timing on the password-equivalent w0s/w1s.
+    let arr = super::barrett::reduce(bytes);
+    Ok(Scalar::reduce(U256::from_be_slice(&arr)))
+}
+
+/// A pure-Rust, `no_std`-friendly SPAKE2+ backend built on the `p256` and
+/// `crypto-bigint` crates, so the crate can run on embedded targets without
+/// linking libcrypto. It is a drop-in peer of [`super::crypto_openssl`]; both
+/// implement [`CryptoUtils`]. The `crypto_rustcrypto` feature selects this one
+/// through the `CryptoBackend` alias in `super::crypto`, and
+/// `CryptoUtils::new()` constructs it.
+#[allow(non_snake_case)]
+pub struct CryptoRustCrypto {
+    // The random scalar x or y, depending on our role.
+    xy: Scalar,
+    w0: Scalar,
+    w1: Scalar,
+    M: ProjectivePoint,
+    N: ProjectivePoint,
+}
+
+impl CryptoUtils for CryptoRustCrypto {
+    #[allow(non_snake_case)]
+    fn new() -> Result<Self, Error> {
+        Ok(CryptoRustCrypto {
+            xy: Scalar::ZERO,
+            w0: Scalar::ZERO,
+            w1: Scalar::ZERO,
+            M: point_from_sec1(&MATTER_M_BIN)?,
+            N: point_from_sec1(&MATTER_N_BIN)?,
+        })
+    }
+
+    fn set_w0_from_w0s(&mut self, w0s: &[u8]) -> Result<(), Error> {
+        // w0 = w0s mod n
+        self.w0 = scalar_mod_order(w0s)?;
+        Ok(())
+    }
+
+    fn set_w1_from_w1s(&mut self, w1s: &[u8]) -> Result<(), Error> {
+        // w1 = w1s mod n
+        self.w1 = scalar_mod_order(w1s)?;
+        Ok(())
+    }
+
+    fn set_w0(&mut self, w0: &[u8]) -> Result<(), Error> {
+        self.w0 = scalar_mod_order(w0)?;
+        Ok(())
+    }
+
+    fn set_w1(&mut self, w1: &[u8]) -> Result<(), Error> {
+        self.w1 = scalar_mod_order(w1)?;
+        Ok(())
+    }
+}
+
+impl CryptoRustCrypto {
+    #[allow(non_snake_case)]
+    fn get_L(&mut self, w1s: &[u8]) -> Result<ProjectivePoint, Error> {
+        // L = w1 * P, with P the curve generator.
+        let w1 = scalar_mod_order(w1s)?;
+        Ok(ProjectivePoint::GENERATOR * w1)
+    }
+
+    #[allow(non_snake_case)]
+    fn get_XY(&mut self, MN: &ProjectivePoint, w0: &Scalar, rand: Scalar) -> ProjectivePoint {
+        // X = x*P + w0*M (or Y = y*P + w0*N). The caller supplies the random
+        // scalar so the prover/verifier can reuse its own entropy source.
+        self.xy = rand;
+        ProjectivePoint::GENERATOR * self.xy + *MN * *w0
+    }
+
+    #[allow(non_snake_case)]
+    fn get_ZV_as_prover(
+        w0: &Scalar,
+        w1: &Scalar,
+        N: &ProjectivePoint,
+        Y: &ProjectivePoint,
+        x: &Scalar,
+    ) -> (ProjectivePoint, ProjectivePoint) {
+        // Z = h*x*(Y - w0*N), V = h*w1*(Y - w0*N); cofactor h = 1 for P-256.
+        let y_minus = *Y - *N * *w0;
+        let Z = y_minus * *x;
+        let V = y_minus * *w1;
+        (Z, V)
+    }
+
+    #[allow(non_snake_case)]
+    fn get_ZV_as_verifier(
+        w0: &Scalar,
+        L: &ProjectivePoint,
+        M: &ProjectivePoint,
+        X: &ProjectivePoint,
+        y: &Scalar,
+    ) -> (ProjectivePoint, ProjectivePoint) {
+        // Z = h*y*(X - w0*M), V = h*y*L; cofactor h = 1 for P-256.
+        let Z = (*X - *M * *w0) * *y;
+        let V = *L * *y;
+        (Z, V)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{point_from_sec1, CryptoRustCrypto};
+    use crate::secure_channel::crypto::CryptoUtils;
+    use crate::secure_channel::spake2p_test_vectors::test_vectors::*;
+    use elliptic_curve::group::GroupEncoding;
+    use p256::{ProjectivePoint, Scalar, U256};
+    use elliptic_curve::ops::Reduce;
+    use elliptic_curve::sec1::ToEncodedPoint;
+
+    fn scalar(bytes: &[u8]) -> Scalar {
+        let mut arr = [0u8; 32];
+        arr.copy_from_slice(bytes);
+        Scalar::reduce(U256::from_be_slice(&arr))
+    }
+
+    fn point_bytes(p: &ProjectivePoint) -> [u8; 65] {
+        let enc = p.to_affine().to_encoded_point(false);
+        let mut out = [0u8; 65];
+        out.copy_from_slice(enc.as_bytes());
+        out
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_get_X() {
+        for t in RFC_T {
+            let mut c = CryptoRustCrypto::new().unwrap();
+            c.set_w0(&t.w0).unwrap();
+            let w0 = c.w0;
+            let X = c.get_XY(&c.M.clone(), &w0, scalar(&t.x));
+            assert_eq!(t.X, point_bytes(&X));
+        }
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_get_ZV_as_prover() {
+        for t in RFC_T {
+            let mut c = CryptoRustCrypto::new().unwrap();
+            c.set_w0(&t.w0).unwrap();
+            c.set_w1(&t.w1).unwrap();
+            let Y = point_from_sec1(&t.Y).unwrap();
+            let (Z, V) =
+                CryptoRustCrypto::get_ZV_as_prover(&c.w0, &c.w1, &c.N, &Y, &scalar(&t.x));
+            assert_eq!(t.Z, point_bytes(&Z));
+            assert_eq!(t.V, point_bytes(&V));
+        }
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_get_ZV_as_verifier() {
+        for t in RFC_T {
+            let mut c = CryptoRustCrypto::new().unwrap();
+            c.set_w0(&t.w0).unwrap();
+            let X = point_from_sec1(&t.X).unwrap();
+            let L = point_from_sec1(&t.L).unwrap();
+            let (Z, V) =
+                CryptoRustCrypto::get_ZV_as_verifier(&c.w0, &L, &c.M, &X, &scalar(&t.y));
+            assert_eq!(t.Z, point_bytes(&Z));
+            assert_eq!(t.V, point_bytes(&V));
+        }
+    }
+}
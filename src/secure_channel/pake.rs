@@ -1,14 +1,29 @@
-use super::spake2p::Spake2P;
+use super::crypto::SoftwareCrypto;
+use super::spake2p::{Spake2P, SPAKE2P_L_SIZE, SPAKE2P_W0_SIZE};
 use crate::error::Error;
 use crate::proto_demux::ProtoCtx;
+use heapless::LinearMap;
 use crate::tlv::*;
 use crate::tlv_common::TagType;
 use crate::tlv_writer::TLVWriter;
 use crate::transport::exchange::ExchangeRole;
 use crate::transport::tx_ctx::TxCtx;
 use log::{error, info};
+#[cfg(feature = "std")]
 use rand::prelude::*;
 
+/// A random-fill function injected into the PASE handlers.
+///
+/// Keeping the entropy source pluggable lets the handlers run under `no_std`,
+/// where there is no thread-local RNG: an embedded caller supplies its own
+/// hardware/DRBG-backed fill routine.
+pub type RandomFill = fn(&mut [u8]);
+
+#[cfg(feature = "std")]
+fn std_rand(buf: &mut [u8]) {
+    rand::thread_rng().fill_bytes(buf);
+}
+
 // This file basically deals with the handlers for the PASE secure channel protocol
 // TLV extraction and encoding is done in this file.
 // We create a Spake2p object and set it up in the exchange-data. This object then
@@ -17,27 +32,124 @@ use rand::prelude::*;
 // As per the spec the iteration count should be between 1000 and 100000
 const ITERATION_COUNT: u32 = 2000;
 
+// As per the spec the salt should be between 16 and 32 bytes
+const MIN_SALT_LEN: usize = 16;
+const MAX_SALT_LEN: usize = 32;
+
 // TODO: Password should be passed inside
 const SPAKE2_PASSWORD: u32 = 123456;
 
-#[derive(Default)]
+// The maximum number of commissioning credentials held at once.
+const MAX_CREDS: usize = 4;
+
+/// A single commissioning credential. Neither the plaintext passcode nor the
+/// secret scalar `w1` is retained; only the salt and the augmented SPAKE2+
+/// verifier `(w0, L = w1·P)` are stored. The verifier is the public material a
+/// commissioner provisions onto the device, so a compromise of the device
+/// cannot recover the password-equivalent secret.
+struct Credential {
+    // Salt storage is sized for the spec maximum; only `salt_len` bytes are used.
+    salt: [u8; MAX_SALT_LEN],
+    salt_len: usize,
+    iter_count: u32,
+    w0: [u8; SPAKE2P_W0_SIZE],
+    l: [u8; SPAKE2P_L_SIZE],
+}
+
+impl Credential {
+    fn salt(&self) -> &[u8] {
+        &self.salt[..self.salt_len]
+    }
+}
+
 pub struct PAKE {
-    // As per the spec the salt should be between 16 to 32 bytes
-    salt: [u8; 16],
-    passwd: u32,
+    // Commissioning credentials, keyed by passcode_id. The peer selects which
+    // one to use via the passcode_id in the PBKDFParamRequest.
+    creds: LinearMap<u16, Credential, MAX_CREDS>,
+    // The entropy source, injected so the handlers can run without std.
+    rand: RandomFill,
 }
 
 impl PAKE {
-    pub fn new() -> Self {
-        // TODO: Can any PBKDF2 calculation be pre-computed here
+    pub fn new(rand: RandomFill) -> Self {
         let mut pake = PAKE {
-            passwd: SPAKE2_PASSWORD,
-            ..Default::default()
+            creds: LinearMap::new(),
+            rand,
         };
-        rand::thread_rng().fill_bytes(&mut pake.salt);
+        // Seed the default credential (passcode_id 0) for backwards compat. The
+        // passcode is only used here to derive the augmented verifier; w1 and
+        // the passcode itself are discarded by `add_credential`.
+        pake.add_credential(0, SPAKE2_PASSWORD);
         pake
     }
 
+    /// Construct a PAKE handler using std's thread-local RNG.
+    #[cfg(feature = "std")]
+    pub fn new_std() -> Self {
+        Self::new(std_rand)
+    }
+
+    /// Register a commissioning credential from a precomputed augmented
+    /// verifier `(w0, L)`. This is the provisioning path for a real device: the
+    /// commissioner derives the verifier off-device and the device never sees
+    /// the passcode or the secret scalar `w1`. The salt length is clamped to the
+    /// spec range [16, 32]: a longer salt is truncated and a shorter one is
+    /// zero-padded.
+    pub fn with_verifier(
+        &mut self,
+        passcode_id: u16,
+        salt: &[u8],
+        iter_count: u32,
+        w0: &[u8; SPAKE2P_W0_SIZE],
+        l: &[u8; SPAKE2P_L_SIZE],
+    ) {
+        let salt_len = salt.len().clamp(MIN_SALT_LEN, MAX_SALT_LEN);
+        let mut cred = Credential {
+            salt: [0; MAX_SALT_LEN],
+            salt_len,
+            iter_count,
+            w0: *w0,
+            l: *l,
+        };
+        let copy_len = salt.len().min(salt_len);
+        cred.salt[..copy_len].copy_from_slice(&salt[..copy_len]);
+        // Overwrite any previous credential with the same id.
+        let _ = self.creds.insert(passcode_id, cred);
+    }
+
+    /// Register a commissioning credential from a plaintext passcode with the
+    /// default iteration count and salt length.
+    ///
+    /// This is a development/test fixture: a production device is provisioned
+    /// with a precomputed verifier via [`with_verifier`] and never holds the
+    /// passcode. Real deployments must not call this.
+    pub fn add_credential(&mut self, passcode_id: u16, passcode: u32) {
+        self.add_credential_with(passcode_id, passcode, ITERATION_COUNT, MIN_SALT_LEN);
+    }
+
+    /// As [`add_credential`], but with an explicit PBKDF2 iteration count and
+    /// salt length. A fresh salt is generated, the augmented verifier `(w0, L)`
+    /// is derived, and the passcode and `w1` are discarded. Also a test fixture.
+    pub fn add_credential_with(
+        &mut self,
+        passcode_id: u16,
+        passcode: u32,
+        iter_count: u32,
+        salt_len: usize,
+    ) {
+        let salt_len = salt_len.clamp(MIN_SALT_LEN, MAX_SALT_LEN);
+        let mut salt = [0u8; MAX_SALT_LEN];
+        (self.rand)(&mut salt[..salt_len]);
+        // Derive the augmented verifier from the passcode, then keep only the
+        // public (w0, L); w1 never leaves this call.
+        let w0w1 =
+            Spake2P::<SoftwareCrypto>::compute_verifier(passcode, iter_count, &salt[..salt_len]);
+        let mut w0 = [0u8; SPAKE2P_W0_SIZE];
+        w0.copy_from_slice(&w0w1[..SPAKE2P_W0_SIZE]);
+        let l = Spake2P::<SoftwareCrypto>::compute_l(&w0w1[SPAKE2P_W0_SIZE..]);
+        self.with_verifier(passcode_id, &salt[..salt_len], iter_count, &w0, &l);
+    }
+
     #[allow(non_snake_case)]
     pub fn handle_pasepake1(
         &mut self,
@@ -55,7 +167,8 @@ impl PAKE {
         let pA = extract_pasepake1_params(proto_ctx.buf)?;
         let mut pB: [u8; 65] = [0; 65];
         let mut cB: [u8; 32] = [0; 32];
-        spake2.start_verifier(self.passwd, ITERATION_COUNT, &self.salt)?;
+        // The verifier for the selected credential was already loaded into the
+        // spake2 object while handling the PBKDFParamRequest.
         spake2.handle_pA(pA, &mut pB, &mut cB)?;
 
         let mut tlvwriter = TLVWriter::new(tx_ctx.get_write_buf());
@@ -84,15 +197,18 @@ impl PAKE {
             initiator_random, initiator_sessid, passcode_id, has_params
         );
 
-        if passcode_id != 0 {
-            error!("Can't yet handle passcode_id != 0");
-            return Err(Error::Invalid);
-        }
+        // Select the commissioning credential the initiator asked for.
+        let cred = self.creds.get(&passcode_id).ok_or_else(|| {
+            error!("No credential for passcode_id {}", passcode_id);
+            Error::Invalid
+        })?;
 
         let mut our_random: [u8; 32] = [0; 32];
-        rand::thread_rng().fill_bytes(&mut our_random);
+        (self.rand)(&mut our_random);
 
-        let mut spake2p = Box::new(Spake2P::new());
+        let mut spake2p = Box::new(Spake2P::<SoftwareCrypto>::new());
+        // Load the augmented verifier (w0, L) for the selected credential.
+        spake2p.start_verifier_with(&cred.w0, &cred.l);
 
         // Generate response
         let mut tlvwriter = TLVWriter::new(tx_ctx.get_write_buf());
@@ -102,8 +218,8 @@ impl PAKE {
         tlvwriter.put_u16(TagType::Context, 3, proto_ctx.session.get_local_sess_id())?;
         if !has_params {
             tlvwriter.put_start_struct(TagType::Context, 4)?;
-            tlvwriter.put_u32(TagType::Context, 1, ITERATION_COUNT)?;
-            tlvwriter.put_str8(TagType::Context, 2, &self.salt)?;
+            tlvwriter.put_u32(TagType::Context, 1, cred.iter_count)?;
+            tlvwriter.put_str8(TagType::Context, 2, cred.salt())?;
             tlvwriter.put_end_container()?;
         }
         tlvwriter.put_end_container()?;
@@ -1,9 +1,12 @@
+use core::marker::PhantomData;
+
 use byteorder::{ByteOrder, LittleEndian};
-use hkdf::Hkdf;
-use hmac::{Hmac, Mac, NewMac};
-use pbkdf2::pbkdf2;
+use elliptic_curve::ops::Reduce;
+use elliptic_curve::sec1::{FromEncodedPoint, ToEncodedPoint};
+use p256::{EncodedPoint, ProjectivePoint, Scalar, U256};
 use sha2::{Digest, Sha256};
 
+use super::crypto::{Crypto, SoftwareCrypto};
 use crate::error::Error;
 
 #[derive(PartialEq)]
@@ -12,10 +15,65 @@ pub enum Spake2Mode {
     Prover,
     Verifier,
 }
-pub struct Spake2P {
+pub struct Spake2P<C: Crypto = SoftwareCrypto> {
     mode: Spake2Mode,
     context: Sha256,
-    w0w1: [u8; (2 * CRYPTO_W_SIZE_BYTES)],
+    // The augmented SPAKE2+ verifier: the scalar w0 and the point L = w1·P. The
+    // verifier deliberately never holds w1, so a commissioned device cannot
+    // recover the password-equivalent secret.
+    w0: [u8; CRYPTO_W_SIZE_BYTES],
+    l: [u8; CRYPTO_PUBLIC_KEY_SIZE_BYTES],
+    // Derived session key and the prover's expected confirmation MAC, retained
+    // between PASEPake2 (emitted here) and PASEPake3 (verified later).
+    Ke: [u8; 16],
+    cA: [u8; 32],
+    // The symmetric/asymmetric primitives are routed through this backend, so a
+    // build can swap in a hardware accelerator without touching the protocol.
+    _crypto: PhantomData<C>,
+}
+
+// An uncompressed P-256 point (0x04 || X || Y).
+const CRYPTO_PUBLIC_KEY_SIZE_BYTES: usize = 65;
+
+// The Matter-defined SPAKE2+ points M and N as uncompressed SEC1 encodings;
+// identical to the values the `CryptoUtils` backends load.
+const MATTER_M_BIN: [u8; CRYPTO_PUBLIC_KEY_SIZE_BYTES] = [
+    0x04, 0x88, 0x6e, 0x2f, 0x97, 0xac, 0xe4, 0x6e, 0x55, 0xba, 0x9d, 0xd7, 0x24, 0x25, 0x79, 0xf2,
+    0x99, 0x3b, 0x64, 0xe1, 0x6e, 0xf3, 0xdc, 0xab, 0x95, 0xaf, 0xd4, 0x97, 0x33, 0x3d, 0x8f, 0xa1,
+    0x2f, 0x5f, 0xf3, 0x55, 0x16, 0x3e, 0x43, 0xce, 0x22, 0x4e, 0x0b, 0x0e, 0x65, 0xff, 0x02, 0xac,
+    0x8e, 0x5c, 0x7b, 0xe0, 0x94, 0x19, 0xc7, 0x85, 0xe0, 0xca, 0x54, 0x7d, 0x55, 0xa1, 0x2e, 0x2d,
+    0x20,
+];
+const MATTER_N_BIN: [u8; CRYPTO_PUBLIC_KEY_SIZE_BYTES] = [
+    0x04, 0xd8, 0xbb, 0xd6, 0xc6, 0x39, 0xc6, 0x29, 0x37, 0xb0, 0x4d, 0x99, 0x7f, 0x38, 0xc3, 0x77,
+    0x07, 0x19, 0xc6, 0x29, 0xd7, 0x01, 0x4d, 0x49, 0xa2, 0x4b, 0x4f, 0x98, 0xba, 0xa1, 0x29, 0x2b,
+    0x49, 0x07, 0xd6, 0x0a, 0xa6, 0xbf, 0xad, 0xe4, 0x50, 0x08, 0xa6, 0x36, 0x33, 0x7f, 0x51, 0x68,
+    0xc6, 0x4d, 0x9b, 0xd3, 0x60, 0x34, 0x80, 0x8c, 0xd5, 0x64, 0x49, 0x0b, 0x1e, 0x65, 0x6e, 0xdb,
+    0xe7,
+];
+
+fn point_from_sec1(bin: &[u8]) -> Result<ProjectivePoint, Error> {
+    let ep = EncodedPoint::from_bytes(bin).map_err(|_| Error::Invalid)?;
+    Option::from(ProjectivePoint::from_encoded_point(&ep)).ok_or(Error::Invalid)
+}
+
+fn point_to_sec1(p: &ProjectivePoint) -> [u8; CRYPTO_PUBLIC_KEY_SIZE_BYTES] {
+    let enc = p.to_affine().to_encoded_point(false);
+    let mut out = [0; CRYPTO_PUBLIC_KEY_SIZE_BYTES];
+    out.copy_from_slice(enc.as_bytes());
+    out
+}
+
+// Reduce a big-endian scalar slice modulo the group order. The 40-byte PBKDF2
+// halves (wider than n) use the constant-time Barrett path to avoid leaking
+// timing on the password-equivalent material; a 32-byte value is already small.
+fn scalar_mod_order(bytes: &[u8]) -> Result<Scalar, Error> {
+    if bytes.len() == CRYPTO_GROUP_SIZE_BYTES {
+        let arr: [u8; CRYPTO_GROUP_SIZE_BYTES] = bytes.try_into().map_err(|_| Error::Invalid)?;
+        return Ok(Scalar::reduce(U256::from_be_slice(&arr)));
+    }
+    let arr = super::barrett::reduce(bytes);
+    Ok(Scalar::reduce(U256::from_be_slice(&arr)))
 }
 
 const SPAKE2P_KEY_CONFIRM_INFO: [u8; 16] = *b"ConfirmationKeys";
@@ -23,12 +81,27 @@ const SPAKE2P_CONTEXT_PREFIX: [u8; 26] = *b"CHIP PAKE V1 Commissioning";
 const CRYPTO_GROUP_SIZE_BYTES: usize = 32;
 const CRYPTO_W_SIZE_BYTES: usize = CRYPTO_GROUP_SIZE_BYTES + 8;
 
-impl Spake2P {
+/// The size of the raw PBKDF2 output (w0 || w1) in bytes, as produced at
+/// registration time before the augmented verifier is derived.
+pub const SPAKE2P_W0W1_SIZE: usize = 2 * CRYPTO_W_SIZE_BYTES;
+
+/// The size of a stored SPAKE2+ verifier scalar (w0), in bytes.
+pub const SPAKE2P_W0_SIZE: usize = CRYPTO_W_SIZE_BYTES;
+
+/// The size of the stored SPAKE2+ verifier point (L = w1·P), an uncompressed
+/// SEC1 encoding.
+pub const SPAKE2P_L_SIZE: usize = CRYPTO_PUBLIC_KEY_SIZE_BYTES;
+
+impl<C: Crypto> Spake2P<C> {
     pub fn new() -> Self {
         let mut s = Spake2P {
             mode: Spake2Mode::Unknown,
-            w0w1: [0; (2 * CRYPTO_W_SIZE_BYTES)],
+            w0: [0; CRYPTO_W_SIZE_BYTES],
+            l: [0; CRYPTO_PUBLIC_KEY_SIZE_BYTES],
             context: Sha256::new(),
+            Ke: [0; 16],
+            cA: [0; 32],
+            _crypto: PhantomData,
         };
         if s.mode == Spake2Mode::Verifier {}
         s.context.update(SPAKE2P_CONTEXT_PREFIX);
@@ -39,10 +112,162 @@ impl Spake2P {
         self.context.update(buf);
     }
 
+    /// Fold the PBKDFParamRequest and PBKDFParamResponse payloads into the
+    /// transcript context, as mandated by the spec. Called once the response is
+    /// assembled, before the first Pake message.
+    pub fn set_context(&mut self, req: &[u8], resp: &[u8]) {
+        self.context.update(req);
+        self.context.update(resp);
+    }
+
+    /// Start the verifier from a passcode. Intended only as a test fixture: a
+    /// real device is provisioned with the augmented verifier via
+    /// [`start_verifier_with`] and never sees the passcode or w1.
     pub fn start_verifier(&mut self, pw: u32, iter: u32, salt: &[u8]) {
+        let w0w1 = Self::compute_verifier(pw, iter, salt);
+        let l = Self::compute_l(&w0w1[CRYPTO_W_SIZE_BYTES..]);
+        self.w0.copy_from_slice(&w0w1[..CRYPTO_W_SIZE_BYTES]);
+        self.l = l;
+        self.mode = Spake2Mode::Verifier;
+    }
+
+    /// Derive the raw SPAKE2+ PBKDF2 output (w0 || w1) from a passcode. The
+    /// commissioner uses this once to produce the augmented verifier (w0, L);
+    /// w1 is then discarded.
+    pub fn compute_verifier(pw: u32, iter: u32, salt: &[u8]) -> [u8; SPAKE2P_W0W1_SIZE] {
         let mut pw_str: [u8; 4] = [0; 4];
         LittleEndian::write_u32(&mut pw_str, pw);
-        pbkdf2::pbkdf2::<Hmac<Sha256>>(&pw_str, salt, iter, &mut self.w0w1);
+        let mut w0w1 = [0; SPAKE2P_W0W1_SIZE];
+        C::pbkdf2(&pw_str, salt, iter, &mut w0w1);
+        w0w1
+    }
+
+    /// Derive L = w1·P (uncompressed SEC1) from the raw w1 bytes. This is run
+    /// off-device so that only the public point L, never w1, is provisioned.
+    pub fn compute_l(w1s: &[u8]) -> [u8; SPAKE2P_L_SIZE] {
+        let w1 = scalar_mod_order(w1s).expect("w1 is a valid scalar width");
+        point_to_sec1(&(ProjectivePoint::GENERATOR * w1))
+    }
+
+    /// Start the verifier from a precomputed augmented verifier: the scalar
+    /// `w0` and the point `L = w1·P`. This is the provisioning path for a real
+    /// device, which holds neither w1 nor the passcode.
+    pub fn start_verifier_with(
+        &mut self,
+        w0: &[u8; SPAKE2P_W0_SIZE],
+        l: &[u8; SPAKE2P_L_SIZE],
+    ) {
+        self.w0 = *w0;
+        self.l = *l;
+        self.mode = Spake2Mode::Verifier;
+    }
+
+    // Assemble the SPAKE2+ transcript TT: the length-prefixed (8-byte
+    // little-endian lengths) concatenation of
+    //   Context || "" || "" || M || N || X || Y || Z || V || w0.
+    #[allow(non_snake_case)]
+    fn build_tt(
+        &self,
+        X: &[u8],
+        Y: &[u8],
+        Z: &[u8],
+        V: &[u8],
+        w0: &Scalar,
+    ) -> heapless::Vec<u8, 1024> {
+        fn add(tt: &mut heapless::Vec<u8, 1024>, buf: &[u8]) {
+            let mut len = [0; 8];
+            LittleEndian::write_u64(&mut len, buf.len() as u64);
+            let _ = tt.extend_from_slice(&len);
+            let _ = tt.extend_from_slice(buf);
+        }
+        let mut tt: heapless::Vec<u8, 1024> = heapless::Vec::new();
+        let context = self.context.clone().finalize();
+        add(&mut tt, context.as_slice());
+        add(&mut tt, b"");
+        add(&mut tt, b"");
+        add(&mut tt, &MATTER_M_BIN);
+        add(&mut tt, &MATTER_N_BIN);
+        add(&mut tt, X);
+        add(&mut tt, Y);
+        add(&mut tt, Z);
+        add(&mut tt, V);
+        add(&mut tt, &w0.to_bytes());
+        tt
+    }
+
+    /// Handle the incoming PASEPake1 as the verifier: `pA` is the prover's `X`.
+    /// Picks a random `y`, produces `pB = Y = y*P + w0*N`, derives the shared
+    /// `Z`/`V`, assembles `TT`, and returns the responder confirmation `cB`. The
+    /// session key `Ke` and the prover's expected `cA` are retained for
+    /// PASEPake3.
+    #[allow(non_snake_case)]
+    pub fn handle_pA(
+        &mut self,
+        pA: &[u8],
+        pB: &mut [u8; CRYPTO_PUBLIC_KEY_SIZE_BYTES],
+        cB: &mut [u8; 32],
+    ) -> Result<(), Error> {
+        let w0 = scalar_mod_order(&self.w0)?;
+        // The backend takes 32-byte big-endian scalars; hand it `w0` reduced to
+        // its canonical width.
+        let mut w0_bytes = [0u8; CRYPTO_GROUP_SIZE_BYTES];
+        w0_bytes.copy_from_slice(w0.to_bytes().as_slice());
+        let X = point_from_sec1(pA)?;
+
+        // y <-$ [0, n). The entropy is drawn through the backend `C` so embedded
+        // builds use their own DRBG.
+        let mut y_bytes = [0u8; CRYPTO_GROUP_SIZE_BYTES];
+        C::rand_fill(&mut y_bytes);
+
+        // Y = y*P + w0*N. The two scalar-multiplications are the heavy P-256 ops
+        // this backend exists to accelerate, so route them through `C` rather
+        // than the in-process `p256` crate; the cheap point addition stays here.
+        let Gy = point_from_sec1(&C::p256_mul_gen(&y_bytes)?)?;
+        let Nw0 = point_from_sec1(&C::p256_mul(&MATTER_N_BIN, &w0_bytes)?)?;
+        let Y = Gy + Nw0;
+
+        // Z = y*(X - w0*M), V = y*L (cofactor h = 1). L = w1*P was derived
+        // off-device and is stored directly; w1 itself is never available here.
+        let Mw0 = point_from_sec1(&C::p256_mul(&MATTER_M_BIN, &w0_bytes)?)?;
+        let x_minus_mw0 = point_to_sec1(&(X - Mw0));
+        let Z = point_from_sec1(&C::p256_mul(&x_minus_mw0, &y_bytes)?)?;
+        let V = point_from_sec1(&C::p256_mul(&self.l, &y_bytes)?)?;
+
+        *pB = point_to_sec1(&Y);
+        let Z_bin = point_to_sec1(&Z);
+        let V_bin = point_to_sec1(&V);
+
+        let tt = self.build_tt(pA, pB, &Z_bin, &V_bin, &w0);
+        let mut cA = [0; 32];
+        Spake2P::get_Ke_and_cAcB(&tt, pA, pB, &mut self.Ke, &mut cA, cB)?;
+        self.cA = cA;
+        Ok(())
+    }
+
+    /// Verify the prover's PASEPake3 confirmation `cA` against the value derived
+    /// while emitting PASEPake2. On success the session is authenticated and the
+    /// derived key is available via `get_session_key`.
+    #[allow(non_snake_case)]
+    pub fn handle_cA(&self, cA: &[u8]) -> Result<(), Error> {
+        // Constant-time compare to avoid leaking how much of the MAC matched.
+        if cA.len() != self.cA.len() {
+            return Err(Error::Invalid);
+        }
+        let mut diff = 0u8;
+        for (a, b) in cA.iter().zip(self.cA.iter()) {
+            diff |= a ^ b;
+        }
+        if diff == 0 {
+            Ok(())
+        } else {
+            Err(Error::Invalid)
+        }
+    }
+
+    /// The derived 128-bit session key, valid only after a successful
+    /// `handle_cA`.
+    pub fn get_session_key(&self) -> &[u8; 16] {
+        &self.Ke
     }
 
     #[inline(always)]
@@ -56,8 +281,7 @@ impl Spake2P {
         cB: &mut [u8],
     ) -> Result<(), Error> {
         // Step 1: Ka || Ke = Hash(TT)
-        let KaKe = Sha256::digest(TT);
-        let KaKe = KaKe.as_slice();
+        let KaKe = C::sha256(TT);
         let KaKe_len = KaKe.len();
         let Ka = &KaKe[0..KaKe_len / 2];
         let ke_internal = &KaKe[(KaKe_len / 2)..];
@@ -68,29 +292,16 @@ impl Spake2P {
         }
 
         // Step 2: KcA || KcB = KDF(nil, Ka, "ConfirmationKeys")
-        let h = Hkdf::<Sha256>::new(None, Ka);
         let mut KcAKcB: [u8; 32] = [0; 32];
         let KcAKcB_len = KcAKcB.len();
-        h.expand(&SPAKE2P_KEY_CONFIRM_INFO, &mut KcAKcB)
-            .map_err(|x| Error::NoSpace)?;
+        C::hkdf_expand(Ka, &SPAKE2P_KEY_CONFIRM_INFO, &mut KcAKcB)?;
 
         let KcA = &KcAKcB[0..(KcAKcB_len / 2)];
         let KcB = &KcAKcB[(KcAKcB_len / 2)..];
 
         // Step 3: cA = HMAC(KcA, pB), cB = HMAC(KcB, pA)
-        let mut mac = Hmac::<Sha256>::new_from_slice(KcA).map_err(|_x| Error::InvalidKeyLength)?;
-        mac.update(pB);
-        let r = mac.finalize().into_bytes();
-        if r.len() == cA.len() {
-            cA.copy_from_slice(r.as_slice());
-        }
-
-        let mut mac = Hmac::<Sha256>::new_from_slice(KcB).map_err(|_x| Error::InvalidKeyLength)?;
-        mac.update(pA);
-        let r = mac.finalize().into_bytes();
-        if r.len() == cB.len() {
-            cB.copy_from_slice(r.as_slice());
-        }
+        C::hmac(KcA, pB, cA)?;
+        C::hmac(KcB, pA, cB)?;
         Ok(())
     }
 }
@@ -98,19 +309,19 @@ impl Spake2P {
 #[cfg(test)]
 mod tests {
     use super::Spake2P;
+    use crate::secure_channel::crypto::SoftwareCrypto;
     use crate::secure_channel::spake2p_test_vectors::test_vectors::*;
 
     #[test]
     fn test_pbkdf2() {
         // These are the vectors from one sample run of chip-tool along with our PBKDFParamResponse
-        let mut spake2 = Spake2P::new();
         let salt = [
             0x4, 0xa1, 0xd2, 0xc6, 0x11, 0xf0, 0xbd, 0x36, 0x78, 0x67, 0x79, 0x7b, 0xfe, 0x82,
             0x36, 0x0,
         ];
-        spake2.start_verifier(123456, 2000, &salt);
+        let w0w1 = Spake2P::<SoftwareCrypto>::compute_verifier(123456, 2000, &salt);
         assert_eq!(
-            spake2.w0w1,
+            w0w1,
             [
                 0xc7, 0x89, 0x33, 0x9c, 0xc5, 0xeb, 0xbc, 0xf6, 0xdf, 0x04, 0xa9, 0x11, 0x11, 0x06,
                 0x4c, 0x15, 0xac, 0x5a, 0xea, 0x67, 0x69, 0x9f, 0x32, 0x62, 0xcf, 0xc6, 0xe9, 0x19,
@@ -129,7 +340,8 @@ mod tests {
             let mut Ke: [u8; 16] = [0; 16];
             let mut cA: [u8; 32] = [0; 32];
             let mut cB: [u8; 32] = [0; 32];
-            Spake2P::get_Ke_and_cAcB(&t.TT, &t.X, &t.Y, &mut Ke, &mut cA, &mut cB).unwrap();
+            Spake2P::<SoftwareCrypto>::get_Ke_and_cAcB(&t.TT, &t.X, &t.Y, &mut Ke, &mut cA, &mut cB)
+                .unwrap();
             assert_eq!(Ke, t.Ke);
             assert_eq!(cA, t.cA);
             assert_eq!(cB, t.cB);
@@ -1,14 +1,29 @@
+use core::any::Any;
 use core::fmt;
-use std::any::Any;
 
 use crate::{error::*, transport::exchange::*};
 use heapless::Vec;
-use log::info;
+use log::{error, info};
 
 const MATTER_AES128_KEY_SIZE: usize = 16;
 
 const EXCHANGES_PER_SESSION: usize = 4;
 
+/// Transport-layer peer address.
+///
+/// The session layer used to hard-code `std::net::IpAddr`, which pinned the
+/// whole transport to `std`. This enum abstracts the peer endpoint instead: the
+/// IP variant is only compiled with the `std` feature, while a BLE/BTP transport
+/// selects the link-layer `Btp` variant (there is no Thread variant) so `SessionMgr` still builds
+/// under `no_std` + `alloc`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Address {
+    #[cfg(feature = "std")]
+    Ip(std::net::IpAddr),
+    // Bluetooth LE device address of a BTP peer.
+    Btp([u8; 6]),
+}
+
 #[derive(Debug, PartialEq)]
 pub enum SessionMode {
     Encrypted,
@@ -24,7 +39,7 @@ impl Default for SessionMode {
 #[derive(Debug, Default)]
 pub struct Session {
     // If this field is None, the rest of the members are ignored
-    peer_addr: Option<std::net::IpAddr>,
+    peer_addr: Option<Address>,
     // I find the session initiator/responder role getting confused with exchange initiator/responder
     // So, we might keep this as enc_key and dec_key for now
     dec_key: [u8; MATTER_AES128_KEY_SIZE],
@@ -48,10 +63,62 @@ pub struct Session {
     // see this child session ID. Keeping it here, makes it easier to manage.
     child_local_sess_id: u16,
     msg_ctr: u32,
+    // Anti-replay reception state: the highest peer counter seen so far plus a
+    // 32-bit sliding-window bitmap of recently seen counters (bit `n` tracks
+    // `max_rx_ctr - n`). `rx_ctr_valid` stays false until the first frame is
+    // received, so the initial counter is always accepted.
+    max_rx_ctr: u32,
+    rx_window: u32,
+    rx_ctr_valid: bool,
     exchanges: [Option<Exchange>; EXCHANGES_PER_SESSION],
+    // Active subscriptions live alongside the exchanges so that a controller's
+    // push subscription survives the exchange that created it. They are dropped
+    // with the session on loss, tearing down the subscription automatically.
+    subscriptions: [Option<Subscription>; SUBSCRIPTIONS_PER_SESSION],
     mode: SessionMode,
 }
 
+const SUBSCRIPTIONS_PER_SESSION: usize = 2;
+
+// A persisted subscription: the negotiated reporting-interval bounds keep the
+// report cadence between the controller's min-interval floor and max-interval
+// ceiling.
+#[derive(Debug)]
+pub struct Subscription {
+    id: u32,
+    min_int_floor: u16,
+    max_int_ceil: u16,
+}
+
+impl Subscription {
+    pub fn new(id: u32, min_int_floor: u16, max_int_ceil: u16) -> Subscription {
+        Subscription {
+            id,
+            min_int_floor,
+            max_int_ceil,
+        }
+    }
+
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    pub fn min_int_floor(&self) -> u16 {
+        self.min_int_floor
+    }
+
+    pub fn max_int_ceil(&self) -> u16 {
+        self.max_int_ceil
+    }
+}
+
+// The width of the anti-replay reception window, in counters.
+const RX_WINDOW_BITS: u32 = 32;
+
+// Flag a session for rekey once the TX counter crosses this high-water mark,
+// well before the u32 counter would roll over and break AES nonce uniqueness.
+const MSG_CTR_REKEY_THRESHOLD: u32 = 0xFFFF_0000;
+
 #[derive(Debug)]
 pub struct CloneData {
     pub dec_key: [u8; MATTER_AES128_KEY_SIZE],
@@ -75,7 +142,7 @@ impl Session {
     // then they eventually get converted into an encrypted session with the new_encrypted_session() which
     // clones from this plaintext session, but acquires the local/peer session IDs and the
     // encryption keys.
-    pub fn new(child_local_sess_id: u16, peer_addr: std::net::IpAddr) -> Session {
+    pub fn new(child_local_sess_id: u16, peer_addr: Address) -> Session {
         Session {
             peer_addr: Some(peer_addr),
             dec_key: [0; MATTER_AES128_KEY_SIZE],
@@ -85,7 +152,11 @@ impl Session {
             peer_sess_id: 0,
             local_sess_id: 0,
             msg_ctr: 1,
+            max_rx_ctr: 0,
+            rx_window: 0,
+            rx_ctr_valid: false,
             exchanges: Default::default(),
+            subscriptions: Default::default(),
             mode: SessionMode::PlainText,
         }
     }
@@ -101,7 +172,11 @@ impl Session {
             peer_sess_id: clone_from.peer_sess_id,
             child_local_sess_id: 0,
             msg_ctr: 1,
+            max_rx_ctr: 0,
+            rx_window: 0,
+            rx_ctr_valid: false,
             exchanges: Default::default(),
+            subscriptions: Default::default(),
             mode: SessionMode::Encrypted,
         };
 
@@ -179,6 +254,40 @@ impl Session {
             .ok_or(Error::NoExchange)
     }
 
+    // Persist a freshly allocated subscription on this session. Fails with
+    // NoSpace once the per-session slots are full, so a controller cannot pin
+    // unbounded state on the device.
+    pub fn add_subscription(&mut self, sub: Subscription) -> Result<(), Error> {
+        if let Some(slot) = self.subscriptions.iter_mut().find(|x| x.is_none()) {
+            *slot = Some(sub);
+            Ok(())
+        } else {
+            Err(Error::NoSpace)
+        }
+    }
+
+    // Tear down a single subscription by id, as requested over the
+    // ShutdownSubscription path. Returns whether a matching one was found.
+    pub fn remove_subscription(&mut self, id: u32) -> bool {
+        if let Some(slot) = self
+            .subscriptions
+            .iter_mut()
+            .find(|x| matches!(x, Some(s) if s.id == id))
+        {
+            *slot = None;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn get_subscription(&mut self, id: u32) -> Option<&mut Subscription> {
+        self.subscriptions
+            .iter_mut()
+            .filter_map(|x| x.as_mut())
+            .find(|s| s.id == id)
+    }
+
     pub fn get_local_sess_id(&self) -> u16 {
         self.local_sess_id
     }
@@ -204,10 +313,78 @@ impl Session {
         self.mode == SessionMode::Encrypted
     }
 
-    pub fn get_msg_ctr(&mut self) -> u32 {
+    pub fn get_msg_ctr(&mut self) -> Result<u32, Error> {
+        if self.msg_ctr == u32::MAX {
+            // Wrapping would reuse a nonce with the current key, so refuse
+            // rather than silently roll over. A rekey must happen first.
+            error!("Message counter exhausted; rekey required");
+            return Err(Error::CounterExhausted);
+        }
         let ctr = self.msg_ctr;
         self.msg_ctr += 1;
-        ctr
+        Ok(ctr)
+    }
+
+    /// Whether the TX counter has climbed past the rekey high-water mark and the
+    /// session should rotate keys before it approaches counter exhaustion.
+    pub fn needs_rekey(&self) -> bool {
+        self.msg_ctr >= MSG_CTR_REKEY_THRESHOLD
+    }
+
+    /// Rotate the session keys in place, installing fresh `enc_key`/`dec_key`
+    /// and resetting the TX counter and anti-replay state, while preserving the
+    /// peer address, session ids and live exchanges so the secure channel is
+    /// not torn down. Fresh key material is supplied via the same `CloneData`
+    /// used to stand up an encrypted session.
+    pub fn begin_rekey(&mut self, clone_from: &CloneData) {
+        self.dec_key = clone_from.dec_key;
+        self.enc_key = clone_from.enc_key;
+        self.att_challenge = clone_from.att_challenge;
+        self.peer_sess_id = clone_from.peer_sess_id;
+        self.msg_ctr = 1;
+        self.max_rx_ctr = 0;
+        self.rx_window = 0;
+        self.rx_ctr_valid = false;
+        self.mode = SessionMode::Encrypted;
+    }
+
+    /// Check an incoming peer message counter against the sliding-window
+    /// duplicate detector, recording it when accepted. Tolerates reordering and
+    /// loss: a counter newer than anything seen advances the window, one inside
+    /// the window is accepted once, and anything too old or already seen is
+    /// rejected. The first counter of a session is always accepted.
+    pub fn check_and_record_rx_ctr(&mut self, ctr: u32) -> Result<(), Error> {
+        if !self.rx_ctr_valid {
+            self.rx_ctr_valid = true;
+            self.max_rx_ctr = ctr;
+            self.rx_window = 1;
+            return Ok(());
+        }
+
+        if ctr > self.max_rx_ctr {
+            let shift = ctr - self.max_rx_ctr;
+            self.rx_window = if shift >= RX_WINDOW_BITS {
+                0
+            } else {
+                self.rx_window << shift
+            };
+            self.rx_window |= 1;
+            self.max_rx_ctr = ctr;
+            Ok(())
+        } else {
+            let offset = self.max_rx_ctr - ctr;
+            if offset >= RX_WINDOW_BITS {
+                error!("Replay detected: counter {} is too old", ctr);
+                return Err(Error::Invalid);
+            }
+            let bit = 1u32 << offset;
+            if self.rx_window & bit != 0 {
+                error!("Replay detected: duplicate counter {}", ctr);
+                return Err(Error::Invalid);
+            }
+            self.rx_window |= bit;
+            Ok(())
+        }
     }
 
     pub fn get_dec_key(&self) -> Option<&[u8]> {
@@ -261,6 +438,7 @@ impl fmt::Display for Session {
 #[derive(Debug)]
 pub struct SessionMgr {
     next_sess_id: u16,
+    next_subscription_id: u32,
     sessions: Vec<Session, 16>,
 }
 
@@ -269,7 +447,19 @@ impl SessionMgr {
         SessionMgr {
             sessions: Vec::new(),
             next_sess_id: 1,
+            next_subscription_id: 1,
+        }
+    }
+
+    // Hand out a process-unique subscription id. Wraps past u32::MAX skipping 0,
+    // mirroring the session-id allocator.
+    pub fn alloc_subscription_id(&mut self) -> u32 {
+        let id = self.next_subscription_id;
+        self.next_subscription_id = self.next_subscription_id.overflowing_add(1).0;
+        if self.next_subscription_id == 0 {
+            self.next_subscription_id = 1;
         }
+        id
     }
 
     fn get_next_sess_id(&mut self) -> u16 {
@@ -294,7 +484,7 @@ impl SessionMgr {
         next_sess_id
     }
 
-    pub fn add(&mut self, peer_addr: std::net::IpAddr) -> Result<(usize, &mut Session), Error> {
+    pub fn add(&mut self, peer_addr: Address) -> Result<(usize, &mut Session), Error> {
         let child_sess_id = self.get_next_sess_id();
         let session = Session::new(child_sess_id, peer_addr);
 
@@ -307,7 +497,7 @@ impl SessionMgr {
         self.sessions.push(session).map_err(|_s| Error::NoSpace)
     }
 
-    fn _get(&self, sess_id: u16, peer_addr: std::net::IpAddr, is_encrypted: bool) -> Option<usize> {
+    fn _get(&self, sess_id: u16, peer_addr: Address, is_encrypted: bool) -> Option<usize> {
         let mode = if is_encrypted {
             SessionMode::Encrypted
         } else {
@@ -321,7 +511,7 @@ impl SessionMgr {
     pub fn get(
         &mut self,
         sess_id: u16,
-        peer_addr: std::net::IpAddr,
+        peer_addr: Address,
         is_encrypted: bool,
     ) -> Option<(usize, &mut Session)> {
         if let Some(index) = self._get(sess_id, peer_addr, is_encrypted) {
@@ -357,17 +547,97 @@ impl fmt::Display for SessionMgr {
 
 #[cfg(test)]
 mod tests {
-    use super::SessionMgr;
-    use std::net::Ipv4Addr;
+    use super::{Address, Session, SessionMgr};
+    use crate::error::Error;
+    use std::net::{IpAddr, Ipv4Addr};
+
+    fn loopback() -> Address {
+        Address::Ip(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)))
+    }
+
+    fn test_session() -> Session {
+        Session::new(1, loopback())
+    }
+
+    #[test]
+    fn test_rx_window_accepts_first_and_rejects_duplicate() {
+        let mut s = test_session();
+        // First counter is accepted regardless of value.
+        assert_eq!(Ok(()), s.check_and_record_rx_ctr(100));
+        // Replaying it is rejected.
+        assert_eq!(Err(Error::Invalid), s.check_and_record_rx_ctr(100));
+        // A newer counter advances the window.
+        assert_eq!(Ok(()), s.check_and_record_rx_ctr(101));
+        // An older-but-in-window counter is accepted once, then rejected.
+        assert_eq!(Ok(()), s.check_and_record_rx_ctr(90));
+        assert_eq!(Err(Error::Invalid), s.check_and_record_rx_ctr(90));
+    }
+
+    #[test]
+    fn test_counter_exhaustion_and_rekey() {
+        use super::{CloneData, MSG_CTR_REKEY_THRESHOLD};
+        let mut s = test_session();
+        assert!(!s.needs_rekey());
+
+        // Climbing past the high-water mark flags the session for rekey.
+        s.msg_ctr = MSG_CTR_REKEY_THRESHOLD;
+        assert!(s.needs_rekey());
+
+        // The very last counter would wrap, so it is refused.
+        s.msg_ctr = u32::MAX;
+        assert_eq!(Err(Error::CounterExhausted), s.get_msg_ctr());
+
+        // Rekeying installs fresh material and resets the counter.
+        let mut clone = CloneData::new(7);
+        clone.enc_key = [0xab; super::MATTER_AES128_KEY_SIZE];
+        s.begin_rekey(&clone);
+        assert!(!s.needs_rekey());
+        assert_eq!(Ok(1), s.get_msg_ctr());
+    }
+
+    #[test]
+    fn test_rx_window_rejects_too_old() {
+        let mut s = test_session();
+        assert_eq!(Ok(()), s.check_and_record_rx_ctr(100));
+        // Beyond the 32-counter window.
+        assert_eq!(Err(Error::Invalid), s.check_and_record_rx_ctr(60));
+        // A large jump forward resets the window but still accepts.
+        assert_eq!(Ok(()), s.check_and_record_rx_ctr(1000));
+    }
+
+    #[test]
+    fn test_subscription_add_and_shutdown() {
+        use super::Subscription;
+        let mut s = test_session();
+        let mut sm = SessionMgr::new();
+
+        let id = sm.alloc_subscription_id();
+        assert_eq!(Ok(()), s.add_subscription(Subscription::new(id, 1, 60)));
+        assert!(s.get_subscription(id).is_some());
+
+        // Filling the remaining slot works, a further one has nowhere to go.
+        let id2 = sm.alloc_subscription_id();
+        assert_eq!(Ok(()), s.add_subscription(Subscription::new(id2, 1, 60)));
+        let id3 = sm.alloc_subscription_id();
+        assert_eq!(
+            Err(Error::NoSpace),
+            s.add_subscription(Subscription::new(id3, 1, 60))
+        );
+
+        // ShutdownSubscription frees the slot, unknown ids report not-found.
+        assert!(s.remove_subscription(id));
+        assert!(!s.remove_subscription(id));
+        assert_eq!(Ok(()), s.add_subscription(Subscription::new(id3, 1, 60)));
+    }
 
     #[test]
     fn test_next_sess_id_doesnt_reuse() {
         let mut sm = SessionMgr::new();
-        sm.add(std::net::IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)))
+        sm.add(loopback())
             .unwrap();
         assert_eq!(sm.get_next_sess_id(), 2);
         assert_eq!(sm.get_next_sess_id(), 3);
-        sm.add(std::net::IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)))
+        sm.add(loopback())
             .unwrap();
         assert_eq!(sm.get_next_sess_id(), 5);
     }
@@ -375,7 +645,7 @@ mod tests {
     #[test]
     fn test_next_sess_id_overflows() {
         let mut sm = SessionMgr::new();
-        sm.add(std::net::IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)))
+        sm.add(loopback())
             .unwrap();
         assert_eq!(sm.get_next_sess_id(), 2);
         sm.next_sess_id = 65534;